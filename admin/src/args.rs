@@ -17,8 +17,11 @@ pub struct Args {
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
+    /// Transfer a project to another user
     ChangeProjectOwner {
-        project_name: String,
+        /// Project to transfer
+        project_id: String,
+        /// User to transfer the project to
         new_user_id: String,
     },
 
@@ -30,6 +30,22 @@ impl Client {
             .await
     }
 
+    pub async fn update_project_owner(
+        &self,
+        project_id: &str,
+        new_user_id: String,
+    ) -> Result<ProjectResponse> {
+        self.inner
+            .put_json(
+                format!("/projects/{project_id}"),
+                Some(ProjectUpdateRequest {
+                    user_id: Some(new_user_id),
+                    ..Default::default()
+                }),
+            )
+            .await
+    }
+
     pub async fn update_project_compute_tier(
         &self,
         project_id: &str,
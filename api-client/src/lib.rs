@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
+use futures::stream::BoxStream;
 use headers::{Authorization, HeaderMapExt};
 use percent_encoding::utf8_percent_encode;
 use reqwest::header::HeaderMap;
@@ -13,12 +15,20 @@ use shuttle_common::models::{
         DeleteCertificateRequest,
     },
     deployment::{
-        DeploymentListResponse, DeploymentRequest, DeploymentResponse, UploadArchiveResponse,
+        DeploymentListResponse, DeploymentRequest, DeploymentRequestGitRepo, DeploymentResponse,
+        UploadArchiveResponse,
     },
-    log::LogsResponse,
+    log::{LogItem, LogsQuery, LogsResponse},
     project::{ProjectCreateRequest, ProjectListResponse, ProjectResponse, ProjectUpdateRequest},
-    resource::{ProvisionResourceRequest, ResourceListResponse, ResourceResponse, ResourceType},
-    team::TeamListResponse,
+    resource::{
+        ProvisionResourceRequest, ResourceListResponse, ResourceResponse, ResourceType,
+        SecretsUpdateRequest,
+    },
+    team::{
+        AddTeamMemberRequest, TeamListResponse, TeamMembersResponse, TeamMembership, TeamRole,
+        UpdateTeamMemberRequest,
+    },
+    telemetry::{TelemetryConfigResponse, TelemetrySinkConfig, TelemetrySinkConfigDiscriminants},
     user::UserResponse,
 };
 use tokio::net::TcpStream;
@@ -32,6 +42,7 @@ use crate::middleware::LoggingMiddleware;
 #[cfg(feature = "tracing")]
 use tracing::{debug, error};
 
+mod logs;
 mod util;
 use util::ToJson;
 
@@ -110,6 +121,18 @@ impl ShuttleApiClient {
         self.post_json(path, Some(deployment_req)).await
     }
 
+    pub async fn deploy_git_repo(
+        &self,
+        project: &str,
+        deployment_req: DeploymentRequestGitRepo,
+    ) -> Result<DeploymentResponse> {
+        self.deploy(
+            project,
+            DeploymentRequest::GitRepo(Box::new(deployment_req)),
+        )
+        .await
+    }
+
     pub async fn upload_archive(
         &self,
         project: &str,
@@ -193,6 +216,42 @@ impl ShuttleApiClient {
         self.get_json(format!("/projects/{project}/resources/secrets"))
             .await
     }
+    pub async fn update_secrets(
+        &self,
+        project: &str,
+        req: SecretsUpdateRequest,
+    ) -> Result<ResourceResponse> {
+        self.put_json(format!("/projects/{project}/resources/secrets"), Some(req))
+            .await
+    }
+    pub async fn set_secrets(
+        &self,
+        project: &str,
+        secrets: BTreeMap<String, String>,
+    ) -> Result<ResourceResponse> {
+        self.update_secrets(
+            project,
+            SecretsUpdateRequest {
+                set: secrets,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+    pub async fn unset_secrets(
+        &self,
+        project: &str,
+        keys: Vec<String>,
+    ) -> Result<ResourceResponse> {
+        self.update_secrets(
+            project,
+            SecretsUpdateRequest {
+                unset: keys,
+                ..Default::default()
+            },
+        )
+        .await
+    }
 
     pub async fn list_certificates(&self, project: &str) -> Result<CertificateListResponse> {
         self.get_json(format!("/projects/{project}/certificates"))
@@ -248,26 +307,113 @@ impl ShuttleApiClient {
         self.delete_json(format!("/projects/{project}")).await
     }
 
-    #[allow(unused)]
-    async fn get_teams_list(&self) -> Result<TeamListResponse> {
+    pub async fn get_telemetry_config(&self, project: &str) -> Result<TelemetryConfigResponse> {
+        self.get_json(format!("/projects/{project}/telemetry/config"))
+            .await
+    }
+
+    pub async fn set_telemetry_sink_config(
+        &self,
+        project: &str,
+        config: TelemetrySinkConfig,
+    ) -> Result<TelemetryConfigResponse> {
+        self.put_json(
+            format!("/projects/{project}/telemetry/sinks/{}", config.as_ref()),
+            Some(config),
+        )
+        .await
+    }
+
+    pub async fn delete_telemetry_sink_config(
+        &self,
+        project: &str,
+        sink: TelemetrySinkConfigDiscriminants,
+    ) -> Result<TelemetryConfigResponse> {
+        self.delete_json(format!(
+            "/projects/{project}/telemetry/sinks/{}",
+            sink.as_ref()
+        ))
+        .await
+    }
+
+    pub async fn get_teams_list(&self) -> Result<TeamListResponse> {
         self.get_json("/teams").await
     }
 
+    pub async fn get_team_members(&self, team_id: &str) -> Result<TeamMembersResponse> {
+        self.get_json(format!("/teams/{team_id}/members")).await
+    }
+
+    pub async fn add_team_member(
+        &self,
+        team_id: &str,
+        req: AddTeamMemberRequest,
+    ) -> Result<String> {
+        self.post_json(format!("/teams/{team_id}/members"), Some(req))
+            .await
+    }
+
+    pub async fn update_team_member(
+        &self,
+        team_id: &str,
+        user_id: &str,
+        role: TeamRole,
+    ) -> Result<TeamMembership> {
+        self.put_json(
+            format!("/teams/{team_id}/members/{user_id}"),
+            Some(UpdateTeamMemberRequest { role }),
+        )
+        .await
+    }
+
+    pub async fn remove_team_member(&self, team_id: &str, user_id: &str) -> Result<String> {
+        self.delete_json(format!("/teams/{team_id}/members/{user_id}"))
+            .await
+    }
+
     pub async fn get_deployment_logs(
         &self,
         project: &str,
         deployment_id: &str,
+        query: &LogsQuery,
     ) -> Result<LogsResponse> {
-        let path = format!("/projects/{project}/deployments/{deployment_id}/logs");
+        let path = format!(
+            "/projects/{project}/deployments/{deployment_id}/logs{}",
+            logs::query_string(query)
+        );
 
         self.get_json(path).await
     }
-    pub async fn get_project_logs(&self, project: &str) -> Result<LogsResponse> {
-        let path = format!("/projects/{project}/logs");
+    pub async fn get_project_logs(&self, project: &str, query: &LogsQuery) -> Result<LogsResponse> {
+        let path = format!("/projects/{project}/logs{}", logs::query_string(query));
 
         self.get_json(path).await
     }
 
+    /// Stream the logs of a deployment as they are produced.
+    /// Reconnects and resumes from the last received log line if the connection drops.
+    pub fn stream_deployment_logs(
+        &self,
+        project: &str,
+        deployment_id: &str,
+        query: LogsQuery,
+    ) -> BoxStream<'static, Result<LogItem>> {
+        let path = format!("/projects/{project}/deployments/{deployment_id}/logs/ws");
+
+        logs::stream_logs(self.clone(), path, query)
+    }
+    /// Stream the logs of all deployments in a project as they are produced.
+    /// Reconnects and resumes from the last received log line if the connection drops.
+    pub fn stream_project_logs(
+        &self,
+        project: &str,
+        query: LogsQuery,
+    ) -> BoxStream<'static, Result<LogItem>> {
+        let path = format!("/projects/{project}/logs/ws");
+
+        logs::stream_logs(self.clone(), path, query)
+    }
+
     pub async fn get_deployments(
         &self,
         project: &str,
@@ -0,0 +1,301 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, StreamExt};
+use shuttle_common::models::log::{LogItem, LogsQuery};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+#[cfg(feature = "tracing")]
+use tracing::{debug, trace};
+
+use crate::ShuttleApiClient;
+
+/// How many times in a row to try reconnecting a dropped log stream before giving up
+const MAX_RECONNECTS: u32 = 5;
+
+/// Turn a [`LogsQuery`] into a query string (including the leading `?`), or an empty string if no filters are set
+pub(crate) fn query_string(query: &LogsQuery) -> String {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    if let Some(head) = query.head {
+        serializer.append_pair("head", &head.to_string());
+    }
+    if let Some(tail) = query.tail {
+        serializer.append_pair("tail", &tail.to_string());
+    }
+    if let Some(since) = query.since {
+        serializer.append_pair("since", &since.to_rfc3339());
+    }
+    if let Some(until) = query.until {
+        serializer.append_pair("until", &until.to_rfc3339());
+    }
+    if let Some(ref source) = query.source {
+        serializer.append_pair("source", source);
+    }
+    if let Some(ref grep) = query.grep {
+        serializer.append_pair("grep", grep);
+    }
+    let query = serializer.finish();
+
+    if query.is_empty() {
+        query
+    } else {
+        format!("?{query}")
+    }
+}
+
+struct LogStreamState {
+    client: ShuttleApiClient,
+    path: String,
+    query: LogsQuery,
+    ws: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+    /// Timestamp of the last received log line, used for resuming after a reconnect
+    last_timestamp: Option<DateTime<Utc>>,
+    /// Lines received with the last timestamp, used for skipping duplicates after a reconnect
+    last_lines: Vec<String>,
+    /// Whether the server is replaying lines from `last_timestamp` after a reconnect
+    resuming: bool,
+    /// Lines from `last_lines` that have not been replayed yet since the reconnect
+    replay: Vec<String>,
+    reconnects: u32,
+    done: bool,
+}
+
+impl LogStreamState {
+    fn path(&self) -> String {
+        let mut query = self.query.clone();
+        // resume after the last received log line
+        if self.last_timestamp.is_some() {
+            query.since = self.last_timestamp;
+            query.tail = None;
+        }
+
+        format!("{}{}", self.path, query_string(&query))
+    }
+
+    /// Start skipping the lines the server replays from `last_timestamp` on a new connection
+    fn resume(&mut self) {
+        self.resuming = true;
+        self.replay = self.last_lines.clone();
+    }
+
+    /// Returns false if this log line was already yielded before a reconnect
+    fn record(&mut self, log: &LogItem) -> bool {
+        if self.resuming {
+            match self.last_timestamp {
+                Some(ts) if log.timestamp < ts => return false,
+                Some(ts) if log.timestamp == ts => {
+                    if let Some(i) = self.replay.iter().position(|line| *line == log.line) {
+                        self.replay.swap_remove(i);
+                        return false;
+                    }
+                }
+                _ => {
+                    // past the replayed range, so everything from here on is new
+                    self.resuming = false;
+                    self.replay.clear();
+                }
+            }
+        }
+
+        match self.last_timestamp {
+            Some(ts) if log.timestamp == ts => self.last_lines.push(log.line.clone()),
+            Some(ts) if log.timestamp < ts => {}
+            _ => {
+                self.last_timestamp = Some(log.timestamp);
+                self.last_lines = vec![log.line.clone()];
+            }
+        }
+        true
+    }
+}
+
+/// Stream log items from the websocket endpoint at `path`.
+///
+/// If the connection drops, it is re-established with a backoff and resumed from the last received log line.
+/// The stream ends when the server closes the connection.
+pub(crate) fn stream_logs(
+    client: ShuttleApiClient,
+    path: String,
+    query: LogsQuery,
+) -> BoxStream<'static, Result<LogItem>> {
+    let state = LogStreamState {
+        client,
+        path,
+        query,
+        ws: None,
+        last_timestamp: None,
+        last_lines: Vec::new(),
+        resuming: false,
+        replay: Vec::new(),
+        reconnects: 0,
+        done: false,
+    };
+
+    futures::stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+        loop {
+            let ws = match state.ws {
+                Some(ref mut ws) => ws,
+                None => {
+                    if state.reconnects > 0 {
+                        let delay = Duration::from_secs(1 << state.reconnects.min(4));
+                        #[cfg(feature = "tracing")]
+                        debug!(?delay, "reconnecting to log stream");
+                        tokio::time::sleep(delay).await;
+                    }
+                    match state.client.ws_get(state.path()).await {
+                        Ok(ws) => {
+                            if state.last_timestamp.is_some() {
+                                state.resume();
+                            }
+                            state.ws.insert(ws)
+                        }
+                        Err(e) => {
+                            state.reconnects += 1;
+                            if state.reconnects > MAX_RECONNECTS {
+                                state.done = true;
+                                return Some((
+                                    Err(e).context("failed to connect to log stream"),
+                                    state,
+                                ));
+                            }
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            match ws.next().await {
+                Some(Ok(Message::Text(text))) => {
+                    let log = match serde_json::from_str::<LogItem>(&text) {
+                        Ok(log) => log,
+                        Err(e) => {
+                            state.done = true;
+                            return Some((Err(e).context("failed to parse log item"), state));
+                        }
+                    };
+                    state.reconnects = 0;
+                    if state.record(&log) {
+                        return Some((Ok(log), state));
+                    }
+                }
+                Some(Ok(Message::Close(_))) => {
+                    #[cfg(feature = "tracing")]
+                    trace!("log stream closed by server");
+                    return None;
+                }
+                Some(Ok(_)) => {}
+                Some(Err(_)) | None => {
+                    #[cfg(feature = "tracing")]
+                    debug!("log stream dropped");
+                    state.ws = None;
+                    state.reconnects += 1;
+                    if state.reconnects > MAX_RECONNECTS {
+                        state.done = true;
+                        return Some((
+                            Err(anyhow::anyhow!(
+                                "log stream dropped {MAX_RECONNECTS} times in a row"
+                            )),
+                            state,
+                        ));
+                    }
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> LogStreamState {
+        LogStreamState {
+            client: ShuttleApiClient::new("http://localhost".to_owned(), None, None, None),
+            path: "/projects/proj/logs/ws".to_owned(),
+            query: LogsQuery {
+                tail: Some(10),
+                source: Some("app".to_owned()),
+                ..Default::default()
+            },
+            ws: None,
+            last_timestamp: None,
+            last_lines: Vec::new(),
+            resuming: false,
+            replay: Vec::new(),
+            reconnects: 0,
+            done: false,
+        }
+    }
+
+    #[test]
+    fn resumes_from_last_timestamp() {
+        let mut state = state();
+        assert_eq!(state.path(), "/projects/proj/logs/ws?tail=10&source=app");
+
+        let ts = DateTime::parse_from_rfc3339("2025-01-01T12:00:00+00:00")
+            .unwrap()
+            .to_utc();
+        assert!(state.record(&LogItem::new(ts, "app".to_owned(), "a".to_owned())));
+        assert_eq!(
+            state.path(),
+            "/projects/proj/logs/ws?since=2025-01-01T12%3A00%3A00%2B00%3A00&source=app"
+        );
+    }
+
+    #[test]
+    fn empty_query_string() {
+        assert_eq!(query_string(&LogsQuery::default()), "");
+        assert_eq!(
+            query_string(&LogsQuery {
+                head: Some(50),
+                grep: Some("error & warn".to_owned()),
+                ..Default::default()
+            }),
+            "?head=50&grep=error+%26+warn"
+        );
+    }
+
+    #[test]
+    fn skips_duplicates_after_reconnect() {
+        let mut state = state();
+        let ts = DateTime::parse_from_rfc3339("2025-01-01T12:00:00+00:00")
+            .unwrap()
+            .to_utc();
+        let earlier = ts - chrono::Duration::seconds(1);
+
+        assert!(state.record(&LogItem::new(ts, "app".to_owned(), "a".to_owned())));
+        assert!(state.record(&LogItem::new(ts, "app".to_owned(), "b".to_owned())));
+        state.resume();
+        // replayed after reconnect
+        assert!(!state.record(&LogItem::new(earlier, "app".to_owned(), "x".to_owned())));
+        assert!(!state.record(&LogItem::new(ts, "app".to_owned(), "a".to_owned())));
+        assert!(!state.record(&LogItem::new(ts, "app".to_owned(), "b".to_owned())));
+        // new lines
+        assert!(state.record(&LogItem::new(ts, "app".to_owned(), "c".to_owned())));
+        let later = ts + chrono::Duration::seconds(1);
+        assert!(state.record(&LogItem::new(later, "app".to_owned(), "a".to_owned())));
+        assert!(!state.resuming);
+    }
+
+    #[test]
+    fn keeps_repeated_lines_without_reconnect() {
+        let mut state = state();
+        let ts = DateTime::parse_from_rfc3339("2025-01-01T12:00:00+00:00")
+            .unwrap()
+            .to_utc();
+
+        assert!(state.record(&LogItem::new(ts, "app".to_owned(), "tick".to_owned())));
+        assert!(state.record(&LogItem::new(ts, "app".to_owned(), "tick".to_owned())));
+
+        // each of the two identical lines is skipped once, a third one is new
+        state.resume();
+        assert!(!state.record(&LogItem::new(ts, "app".to_owned(), "tick".to_owned())));
+        assert!(!state.record(&LogItem::new(ts, "app".to_owned(), "tick".to_owned())));
+        assert!(state.record(&LogItem::new(ts, "app".to_owned(), "tick".to_owned())));
+    }
+}
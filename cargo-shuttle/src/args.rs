@@ -7,12 +7,19 @@ use std::{
 
 use anyhow::{bail, Context};
 use cargo_metadata::MetadataCommand;
+use chrono::{DateTime, Duration, Utc};
 use clap::{
     builder::{OsStringValueParser, PossibleValue, TypedValueParser},
-    Args, Parser, Subcommand, ValueEnum,
+    ArgGroup, Args, Parser, Subcommand, ValueEnum,
 };
 use clap_complete::Shell;
-use shuttle_common::{constants::EXAMPLES_REPO, models::resource::ResourceType};
+use shuttle_common::{
+    constants::EXAMPLES_REPO,
+    models::{
+        log::LogsQuery, project::ComputeTier, resource::ResourceType, team::TeamRole,
+        telemetry::TelemetrySinkConfigDiscriminants,
+    },
+};
 
 #[derive(Parser)]
 #[command(
@@ -35,6 +42,15 @@ pub struct ShuttleArgs {
     /// Turn on tracing output for Shuttle libraries. (WARNING: can print sensitive data)
     #[arg(global = true, long, env = "SHUTTLE_DEBUG")]
     pub debug: bool,
+    /// What format to print output in
+    #[arg(
+        global = true,
+        long = "output-format",
+        visible_alias = "format",
+        env = "SHUTTLE_OUTPUT_MODE",
+        default_value = "normal"
+    )]
+    pub output_mode: OutputMode,
     #[command(flatten)]
     pub project_args: ProjectArgs,
 
@@ -42,6 +58,17 @@ pub struct ShuttleArgs {
     pub cmd: Command,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputMode {
+    /// Human-readable text and tables
+    #[default]
+    Normal,
+    /// A JSON document
+    Json,
+    /// Newline-delimited JSON, one object per line
+    Ndjson,
+}
+
 /// Global args for subcommands that deal with projects
 #[derive(Args, Clone, Debug)]
 pub struct ProjectArgs {
@@ -116,6 +143,15 @@ pub enum Command {
     /// Manage SSL certificates for custom domains
     #[command(subcommand, visible_alias = "cert")]
     Certificate(CertificateCommand),
+    /// Manage the secrets of a project
+    #[command(subcommand, visible_alias = "secret")]
+    Secrets(SecretsCommand),
+    /// Manage the members of your teams
+    #[command(subcommand)]
+    Team(TeamCommand),
+    /// Manage exporting the telemetry of a project to external sinks
+    #[command(subcommand)]
+    Telemetry(TelemetryCommand),
     /// Show info about your Shuttle account
     #[command(visible_alias = "acc")]
     Account,
@@ -217,6 +253,23 @@ pub enum ResourceCommand {
         #[command(flatten)]
         confirmation: ConfirmationArgs,
     },
+    /// Run pending sqlx migrations against a database resource and list the applied versions
+    #[cfg(feature = "migrate")]
+    Migrate {
+        /// Type of the database resource to migrate.
+        /// Use the string in the 'Type' column as displayed in the `resource list` command.
+        /// For example, 'database::shared::postgres'.
+        resource_type: ResourceType,
+        /// Directory with the migrations, relative to the working directory
+        #[arg(long, default_value = "migrations")]
+        source: PathBuf,
+        /// List the pending migrations without running them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
     /// Dump a resource
     #[command(hide = true)] // not yet supported on shuttle.dev
     Dump {
@@ -227,6 +280,98 @@ pub enum ResourceCommand {
     },
 }
 
+/// Args for subcommands that deal with a team
+#[derive(Args)]
+pub struct TeamArgs {
+    /// ID of the team. Can be left out if you are a member of only one team.
+    #[arg(long = "team")]
+    pub team_id: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum TeamCommand {
+    /// List the teams you are a member of
+    #[command(visible_alias = "ls")]
+    List {
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// List the members and pending invites of a team
+    Members {
+        #[command(flatten)]
+        team: TeamArgs,
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Add a user to a team by user ID, or invite them by email address
+    Invite {
+        /// User ID or email address of the user
+        user: String,
+        /// Role of the user in the team: owner, admin or member
+        #[arg(long, default_value = "member")]
+        role: TeamRole,
+        #[command(flatten)]
+        team: TeamArgs,
+    },
+    /// Remove a user from a team
+    #[command(visible_alias = "rm")]
+    Remove {
+        /// User ID of the member
+        user_id: String,
+        #[command(flatten)]
+        team: TeamArgs,
+        #[command(flatten)]
+        confirmation: ConfirmationArgs,
+    },
+    /// Change the role of a team member
+    SetRole {
+        /// User ID of the member
+        user_id: String,
+        /// New role of the user in the team: owner, admin or member
+        role: TeamRole,
+        #[command(flatten)]
+        team: TeamArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TelemetryCommand {
+    /// List the telemetry sinks and whether the project exports to them
+    #[command(visible_alias = "ls")]
+    List {
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Start exporting telemetry to a sink. Prompts for the sink's configuration.
+    Enable { sink: TelemetrySink },
+    /// Stop exporting telemetry to a sink
+    Disable { sink: TelemetrySink },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum TelemetrySink {
+    /// Betterstack
+    Betterstack,
+    /// Datadog
+    Datadog,
+    /// Grafana Cloud
+    #[value(alias = "grafana_cloud")]
+    GrafanaCloud,
+    /// Any OpenTelemetry collector with an OTLP/HTTP endpoint
+    Otlp,
+}
+
+impl From<TelemetrySink> for TelemetrySinkConfigDiscriminants {
+    fn from(sink: TelemetrySink) -> Self {
+        match sink {
+            TelemetrySink::Betterstack => Self::Betterstack,
+            TelemetrySink::Datadog => Self::Datadog,
+            TelemetrySink::GrafanaCloud => Self::GrafanaCloud,
+            TelemetrySink::Otlp => Self::Otlp,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub enum CertificateCommand {
     /// Add an SSL certificate for a custom domain
@@ -250,6 +395,48 @@ pub enum CertificateCommand {
     },
 }
 
+#[derive(Subcommand)]
+pub enum SecretsCommand {
+    /// List the secrets of a project
+    #[command(visible_alias = "ls")]
+    List {
+        /// Show the values of the secrets
+        #[arg(long, default_value_t = false)]
+        show_values: bool,
+
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Add or update secrets
+    Set {
+        /// Secrets as KEY=VALUE, or KEY to be prompted for the value
+        #[arg(required = true)]
+        secrets: Vec<String>,
+    },
+    /// Remove secrets
+    #[command(visible_alias = "rm")]
+    Unset {
+        /// Keys of the secrets to remove
+        #[arg(required = true)]
+        keys: Vec<String>,
+        #[command(flatten)]
+        confirmation: ConfirmationArgs,
+    },
+    /// Add or update secrets from a TOML file, such as Secrets.toml
+    Import {
+        /// Path to the file, or '-' to read from stdin
+        file: PathBuf,
+    },
+    /// Print the secrets in TOML format
+    Export,
+    /// Show which secrets differ between the local secrets files and the deployed project.
+    /// Values are not shown.
+    Diff {
+        #[command(flatten)]
+        secret_args: SecretsArgs,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum ProjectCommand {
     /// Create a project on Shuttle
@@ -278,6 +465,26 @@ pub enum ProjectCommand {
 pub enum ProjectUpdateCommand {
     /// Rename the project, including its default subdomain
     Name { name: String },
+    /// Change the compute tier that the project's deployments run on
+    Compute {
+        /// Compute tier to run on: xs, s, m, l, xl or xxl
+        tier: ComputeTier,
+    },
+    /// Transfer the project to a team, another user, or back to your personal account
+    #[command(group(ArgGroup::new("owner").required(true)))]
+    Transfer {
+        /// ID of the team to transfer the project to
+        #[arg(long, group = "owner")]
+        team: Option<String>,
+        /// ID of the user to transfer the project to
+        #[arg(long, group = "owner")]
+        user: Option<String>,
+        /// Remove the project from its team and make it a personal project
+        #[arg(long, group = "owner")]
+        personal: bool,
+        #[command(flatten)]
+        confirmation: ConfirmationArgs,
+    },
 }
 
 #[derive(Args, Debug)]
@@ -313,6 +520,15 @@ pub struct DeployArgs {
     /// WIP: Deploy this Docker image instead of building one
     #[arg(long, short = 'i', hide = true)]
     pub image: Option<String>,
+    /// Build the deployment from this git repository URL instead of uploading the local files
+    #[arg(long, value_name = "URL", conflicts_with_all = ["image", "output_archive"])]
+    pub git: Option<String>,
+    /// The branch, tag, or commit of the git repository to deploy
+    #[arg(long = "ref", value_name = "REF", requires = "git")]
+    pub git_ref: Option<String>,
+    /// The path of the Cargo workspace in the git repository, if not in the root
+    #[arg(long, value_name = "PATH", requires = "git")]
+    pub subdirectory: Option<String>,
 
     /// Allow deployment with uncommitted files
     #[arg(long, visible_alias = "ad")]
@@ -320,13 +536,47 @@ pub struct DeployArgs {
     /// Output the deployment archive to a file instead of sending a deployment request
     #[arg(long)]
     pub output_archive: Option<PathBuf>,
+    /// Use the mold linker (overrides `build.mold` in Shuttle.toml)
+    #[arg(long, overrides_with = "no_mold")]
+    pub mold: bool,
+    /// Don't use the mold linker (overrides `build.mold` in Shuttle.toml)
+    #[arg(long, overrides_with = "mold")]
+    pub no_mold: bool,
+    /// Use cargo chef for caching dependencies (overrides `build.cargo_chef` in Shuttle.toml)
+    #[arg(long, overrides_with = "no_cargo_chef")]
+    pub cargo_chef: bool,
+    /// Don't use cargo chef for caching dependencies (overrides `build.cargo_chef` in Shuttle.toml)
+    #[arg(long, overrides_with = "cargo_chef")]
+    pub no_cargo_chef: bool,
+    /// Build with this cargo profile instead of release (overrides `build.profile` in Shuttle.toml)
+    #[arg(long)]
+    pub profile: Option<String>,
 
     #[command(flatten)]
     pub tracking_args: DeploymentTrackingArgs,
 
+    #[command(flatten)]
+    pub service_args: ServiceSelectionArgs,
+
+    #[command(flatten)]
+    pub feature_args: CargoFeatureArgs,
+
     #[command(flatten)]
     pub secret_args: SecretsArgs,
 }
+
+impl DeployArgs {
+    /// Whether `--mold` or `--no-mold` was given
+    pub fn mold_override(&self) -> Option<bool> {
+        flag_override(self.mold, self.no_mold)
+    }
+
+    /// Whether `--cargo-chef` or `--no-cargo-chef` was given
+    pub fn cargo_chef_override(&self) -> Option<bool> {
+        flag_override(self.cargo_chef, self.no_cargo_chef)
+    }
+}
+
 #[derive(Args, Default)]
 pub struct DeploymentTrackingArgs {
     /// Don't follow the deployment status, exit after the operation begins
@@ -348,15 +598,87 @@ pub struct RunArgs {
     /// Use release mode for building the project
     #[arg(long, short = 'r')]
     pub release: bool,
+    /// Build with this cargo profile instead of dev or release
+    #[arg(long, conflicts_with = "release")]
+    pub profile: Option<String>,
     /// Don't display timestamps and log origin tags
     #[arg(long)]
     pub raw: bool,
     /// Uses bacon crate to run the project in watch mode
     #[arg(long)]
     pub bacon: bool,
+    /// Run every Shuttle service in the workspace, each on its own port
+    #[arg(long, conflicts_with_all = ["package", "bin", "bacon"])]
+    pub all: bool,
+
+    #[command(flatten)]
+    pub service_args: ServiceSelectionArgs,
+
+    #[command(flatten)]
+    pub feature_args: CargoFeatureArgs,
 
     #[command(flatten)]
     pub secret_args: SecretsArgs,
+
+    #[command(flatten)]
+    pub otel_args: OtelArgs,
+}
+
+#[derive(Args, Debug, Default)]
+#[command(next_help_heading = "OpenTelemetry options")]
+pub struct OtelArgs {
+    /// Start a local OpenTelemetry collector that prints the spans, logs, and metrics of the service.
+    /// The service exports to it when shuttle-runtime's `setup-otel-exporter` feature is enabled.
+    #[arg(long, conflicts_with = "bacon")]
+    pub otel: bool,
+    /// Append the received telemetry to this file as OTLP JSON lines instead of printing it
+    #[arg(long, requires = "otel")]
+    pub otel_file: Option<PathBuf>,
+    /// Port of the collector's OTLP/gRPC receiver
+    #[arg(long, default_value_t = 4317, requires = "otel")]
+    pub otel_grpc_port: u16,
+    /// Port of the collector's OTLP/HTTP receiver, which the service exports to
+    #[arg(long, default_value_t = 4318, requires = "otel")]
+    pub otel_http_port: u16,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct CargoFeatureArgs {
+    /// Comma-separated list of features to activate (overrides `build.features` in Shuttle.toml)
+    #[arg(long, short = 'F', value_delimiter = ',')]
+    pub features: Vec<String>,
+    /// Do not activate the default features (overrides `build.no_default_features` in Shuttle.toml)
+    #[arg(long, overrides_with = "default_features")]
+    pub no_default_features: bool,
+    /// Activate the default features (overrides `build.no_default_features` in Shuttle.toml)
+    #[arg(long, overrides_with = "no_default_features")]
+    pub default_features: bool,
+}
+
+impl CargoFeatureArgs {
+    /// Whether `--no-default-features` or `--default-features` was given
+    pub fn no_default_features_override(&self) -> Option<bool> {
+        flag_override(self.no_default_features, self.default_features)
+    }
+}
+
+/// Helper function to turn a `--flag`/`--no-flag` pair into an override, if either of them was given
+fn flag_override(flag: bool, negated: bool) -> Option<bool> {
+    match (flag, negated) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ServiceSelectionArgs {
+    /// Which package in the workspace to build (overrides `build.package` in Shuttle.toml)
+    #[arg(long)]
+    pub package: Option<String>,
+    /// Which binary in the package to build (overrides `build.bin` in Shuttle.toml)
+    #[arg(long)]
+    pub bin: Option<String>,
 }
 
 #[derive(Args, Debug, Default)]
@@ -364,6 +686,9 @@ pub struct SecretsArgs {
     /// Use this secrets file instead
     #[arg(long, value_parser = OsStringValueParser::new().try_map(parse_path))]
     pub secrets: Option<PathBuf>,
+    /// Apply overrides from Secrets.<ENV>.toml (defaults to 'dev' in local runs)
+    #[arg(long, value_name = "ENV")]
+    pub secrets_env: Option<String>,
 }
 
 #[derive(Args, Clone, Debug, Default)]
@@ -481,26 +806,74 @@ pub struct LogsArgs {
     #[arg(short, long)]
     /// View logs from the most recent deployment (which is not always the latest running one)
     pub latest: bool,
-    #[arg(short, long, hide = true)]
-    /// Follow log output
+    #[arg(short, long)]
+    /// Follow log output as it is produced
     pub follow: bool,
     /// Don't display timestamps and log origin tags
     #[arg(long)]
     pub raw: bool,
     /// View the first N log lines
-    #[arg(long, group = "output_mode", hide = true)]
+    #[arg(long, group = "line_range", conflicts_with = "follow")]
     pub head: Option<u32>,
     /// View the last N log lines
-    #[arg(long, group = "output_mode", hide = true)]
+    #[arg(long, group = "line_range")]
     pub tail: Option<u32>,
     /// View all log lines
-    #[arg(long, group = "output_mode", hide = true)]
+    #[arg(long, group = "line_range", hide = true)]
     pub all: bool,
+    /// View log lines since this time (RFC 3339 timestamp, or relative like '30s', '10m', '2h', '1d')
+    #[arg(long, value_parser = parse_log_time)]
+    pub since: Option<DateTime<Utc>>,
+    /// View log lines until this time (RFC 3339 timestamp, or relative like '30s', '10m', '2h', '1d')
+    #[arg(long, value_parser = parse_log_time)]
+    pub until: Option<DateTime<Utc>>,
+    /// Only view log lines from this source, for example 'app' or 'build'
+    #[arg(long)]
+    pub source: Option<String>,
+    /// Only view log lines that contain this text
+    #[arg(long)]
+    pub grep: Option<String>,
     /// Get logs from all deployments instead of one deployment
     #[arg(long)]
     pub all_deployments: bool,
 }
 
+impl LogsArgs {
+    pub fn query(&self) -> LogsQuery {
+        LogsQuery {
+            head: self.head,
+            tail: self.tail,
+            since: self.since,
+            until: self.until,
+            source: self.source.clone(),
+            grep: self.grep.clone(),
+        }
+    }
+}
+
+/// Helper function to parse an RFC 3339 timestamp, or a time relative to now (such as '10m')
+fn parse_log_time(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.to_utc());
+    }
+
+    let invalid = || {
+        format!("invalid time {s:?}: expected an RFC 3339 timestamp or a relative time like '10m'")
+    };
+    let (amount, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let duration = match unit {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        _ => None,
+    }
+    .ok_or_else(invalid)?;
+
+    Utc::now().checked_sub_signed(duration).ok_or_else(invalid)
+}
+
 /// Helper function to parse and return the absolute path
 fn parse_path(path: OsString) -> Result<PathBuf, io::Error> {
     dunce::canonicalize(&path).map_err(|e| {
@@ -607,6 +980,109 @@ mod tests {
         assert_eq!(init_args.git_template().unwrap(), None);
     }
 
+    #[test]
+    fn test_parse_log_time() {
+        assert_eq!(
+            parse_log_time("2025-01-01T12:00:00+02:00").unwrap(),
+            DateTime::parse_from_rfc3339("2025-01-01T10:00:00Z").unwrap()
+        );
+
+        let before = Utc::now();
+        let t = parse_log_time("10m").unwrap();
+        assert!(t >= before - Duration::minutes(10));
+        assert!(t <= Utc::now() - Duration::minutes(10));
+        assert!(parse_log_time("2d").unwrap() < before - Duration::days(1));
+
+        assert!(parse_log_time("10").is_err());
+        assert!(parse_log_time("m").is_err());
+        assert!(parse_log_time("10y").is_err());
+        assert!(parse_log_time("yesterday").is_err());
+        assert!(parse_log_time("100000000d").is_err());
+    }
+
+    #[test]
+    fn test_project_transfer_requires_one_owner() {
+        let parse = |args: &[&str]| {
+            ShuttleArgs::try_parse_from(
+                ["shuttle", "project", "update", "transfer"]
+                    .iter()
+                    .chain(args),
+            )
+        };
+
+        let err = parse(&[]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+        let err = parse(&["--team", "team_1", "--personal"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        let args = parse(&["--personal", "--yes"]).unwrap();
+        let Command::Project(ProjectCommand::Update(ProjectUpdateCommand::Transfer {
+            team,
+            user,
+            personal,
+            confirmation,
+        })) = args.cmd
+        else {
+            panic!("expected a project transfer command");
+        };
+        assert_eq!((team, user, personal), (None, None, true));
+        assert!(confirmation.yes);
+    }
+
+    #[test]
+    fn test_otel_ports_require_otel() {
+        let parse =
+            |args: &[&str]| ShuttleArgs::try_parse_from(["shuttle", "run"].iter().chain(args));
+
+        assert!(parse(&[]).is_ok());
+        let err = parse(&["--otel-grpc-port", "5317"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+        let err = parse(&["--otel-http-port", "5318"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+
+        let args = parse(&["--otel", "--otel-http-port", "5318"]).unwrap();
+        let Command::Run(run_args) = args.cmd else {
+            panic!("expected a run command");
+        };
+        assert_eq!(run_args.otel_args.otel_grpc_port, 4317);
+        assert_eq!(run_args.otel_args.otel_http_port, 5318);
+    }
+
+    #[test]
+    fn test_build_flag_overrides() {
+        let deploy_args = |args: &[&str]| {
+            let args =
+                ShuttleArgs::try_parse_from(["shuttle", "deploy"].iter().chain(args)).unwrap();
+            let Command::Deploy(deploy_args) = args.cmd else {
+                panic!("expected a deploy command");
+            };
+            deploy_args
+        };
+
+        let args = deploy_args(&[]);
+        assert_eq!(args.mold_override(), None);
+        assert_eq!(args.cargo_chef_override(), None);
+        assert_eq!(args.feature_args.no_default_features_override(), None);
+
+        let args = deploy_args(&["--no-mold", "--no-cargo-chef", "--default-features"]);
+        assert_eq!(args.mold_override(), Some(false));
+        assert_eq!(args.cargo_chef_override(), Some(false));
+        assert_eq!(
+            args.feature_args.no_default_features_override(),
+            Some(false)
+        );
+
+        // the last flag of a pair wins
+        let args = deploy_args(&[
+            "--no-mold",
+            "--mold",
+            "--default-features",
+            "--no-default-features",
+        ]);
+        assert_eq!(args.mold_override(), Some(true));
+        assert_eq!(args.feature_args.no_default_features_override(), Some(true));
+    }
+
     #[test]
     fn workspace_path() {
         let project_args = ProjectArgs {
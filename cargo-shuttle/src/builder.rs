@@ -42,6 +42,50 @@ impl BuiltService {
     }
 }
 
+const NO_SHUTTLE_PACKAGES: &str = "Did not find any packages that Shuttle can run. \
+    Make sure your crate has a binary target that uses `#[shuttle_runtime::main]`.";
+
+/// Which Shuttle service in the workspace to build.
+/// Can be left empty if the workspace only has one.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ServiceSelection {
+    /// Name of the package
+    pub package: Option<String>,
+    /// Name of the binary target in the package
+    pub bin: Option<String>,
+    /// Build every Shuttle package in the workspace (`package` and `bin` are ignored)
+    pub all: bool,
+}
+
+/// Cargo features to build a Shuttle package with
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CargoFeatures {
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+}
+
+impl CargoFeatures {
+    /// The configured features, or the package's `shuttle` feature without the default features
+    /// if it has one and nothing was configured
+    pub fn for_package(&self, package: &Package) -> CargoFeatures {
+        if *self == CargoFeatures::default() && package.features.contains_key("shuttle") {
+            CargoFeatures {
+                features: vec!["shuttle".to_owned()],
+                no_default_features: true,
+            }
+        } else {
+            self.clone()
+        }
+    }
+}
+
+/// A Shuttle package and the binary target in it to build
+#[derive(Clone, Debug)]
+pub struct SelectedService {
+    pub package: Package,
+    pub bin: String,
+}
+
 fn extract_shuttle_toml_name(path: PathBuf) -> anyhow::Result<String> {
     let shuttle_toml =
         read_to_string(path.as_path()).map_err(|_| anyhow!("{} not found", path.display()))?;
@@ -62,9 +106,11 @@ fn extract_shuttle_toml_name(path: PathBuf) -> anyhow::Result<String> {
 /// Given a project directory path, builds the crate
 pub async fn build_workspace(
     project_path: &Path,
-    release_mode: bool,
+    profile: Option<&str>,
     tx: tokio::sync::mpsc::Sender<String>,
     deployment: bool,
+    selection: &ServiceSelection,
+    features: &CargoFeatures,
 ) -> anyhow::Result<Vec<BuiltService>> {
     let project_path = project_path.to_owned();
     let manifest_path = project_path.join("Cargo.toml");
@@ -101,19 +147,33 @@ pub async fn build_workspace(
 
     let metadata = async_cargo_metadata(manifest_path.as_path()).await?;
     let packages = find_shuttle_packages(&metadata)?;
-    if packages.is_empty() {
-        bail!(
-            "Did not find any packages that Shuttle can run. \
-            Make sure your crate has a binary target that uses `#[shuttle_runtime::main]`."
-        );
-    }
+    let selected = if selection.all {
+        if packages.is_empty() {
+            bail!(NO_SHUTTLE_PACKAGES);
+        }
+        packages
+            .iter()
+            .map(|package| {
+                select_shuttle_service(
+                    &packages,
+                    &ServiceSelection {
+                        package: Some(package.name.clone()),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+    } else {
+        vec![select_shuttle_service(&packages, selection)?]
+    };
 
     let services = compile(
-        packages,
-        release_mode,
+        selected,
+        profile,
         project_path.clone(),
         metadata.target_directory.clone(),
         deployment,
+        features,
         tx.clone(),
     )
     .await?;
@@ -122,6 +182,15 @@ pub async fn build_workspace(
     Ok(services)
 }
 
+/// The directory in the target directory that cargo puts the artifacts of a profile in
+fn profile_dir(profile: Option<&str>) -> &str {
+    match profile {
+        None | Some("dev") | Some("test") => "debug",
+        Some("bench") => "release",
+        Some(profile) => profile,
+    }
+}
+
 pub async fn async_cargo_metadata(manifest_path: &Path) -> anyhow::Result<Metadata> {
     let metadata = {
         // Modified implementaion of `cargo_metadata::MetadataCommand::exec` (from v0.15.3).
@@ -177,6 +246,89 @@ pub fn find_shuttle_packages(metadata: &Metadata) -> anyhow::Result<Vec<Package>
     Ok(packages)
 }
 
+/// Pick the package and binary target to build from the Shuttle packages in a workspace.
+/// Errors with a list of candidates if the selection is ambiguous or does not match anything.
+pub fn select_shuttle_service(
+    packages: &[Package],
+    selection: &ServiceSelection,
+) -> anyhow::Result<SelectedService> {
+    if packages.is_empty() {
+        bail!(NO_SHUTTLE_PACKAGES);
+    }
+    let package_names = || {
+        packages
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let package = match (&selection.package, &selection.bin) {
+        (Some(name), _) => packages.iter().find(|p| &p.name == name).ok_or_else(|| {
+            anyhow!(
+                "Package '{name}' is not a Shuttle service in this workspace. Candidates: {}",
+                package_names()
+            )
+        })?,
+        (None, Some(bin)) => {
+            let mut matching = packages
+                .iter()
+                .filter(|p| p.targets.iter().any(|t| t.is_bin() && &t.name == bin));
+            match (matching.next(), matching.next()) {
+                (Some(package), None) => package,
+                (None, _) => bail!(
+                    "No Shuttle package in this workspace has a binary named '{bin}'. Candidates: {}",
+                    package_names()
+                ),
+                (Some(_), Some(_)) => bail!(
+                    "Multiple Shuttle packages have a binary named '{bin}'. \
+                    Select one with `--package`. Candidates: {}",
+                    package_names()
+                ),
+            }
+        }
+        (None, None) => match packages {
+            [package] => package,
+            _ => bail!(
+                "Found multiple Shuttle services in this workspace. \
+                Select one with `--package` or `build.package` in Shuttle.toml. Candidates: {}",
+                package_names()
+            ),
+        },
+    };
+
+    let bins = package
+        .targets
+        .iter()
+        .filter(|t| t.is_bin())
+        .map(|t| t.name.as_str())
+        .collect::<Vec<_>>();
+    let bin = match &selection.bin {
+        Some(bin) if bins.contains(&bin.as_str()) => bin.clone(),
+        Some(bin) => bail!(
+            "Package '{}' has no binary named '{bin}'. Candidates: {}",
+            package.name,
+            bins.join(", ")
+        ),
+        None => match bins.as_slice() {
+            [bin] => bin.to_string(),
+            // cargo's default binary has the same name as the package
+            _ if bins.contains(&package.name.as_str()) => package.name.clone(),
+            _ => bail!(
+                "Package '{}' has multiple binaries. \
+                Select one with `--bin` or `build.bin` in Shuttle.toml. Candidates: {}",
+                package.name,
+                bins.join(", ")
+            ),
+        },
+    };
+
+    Ok(SelectedService {
+        package: package.to_owned(),
+        bin,
+    })
+}
+
 // Only used in deployer
 pub async fn clean_crate(project_path: &Path) -> anyhow::Result<()> {
     let manifest_path = project_path.join("Cargo.toml");
@@ -199,11 +351,12 @@ pub async fn clean_crate(project_path: &Path) -> anyhow::Result<()> {
 }
 
 async fn compile(
-    packages: Vec<Package>,
-    release_mode: bool,
+    services: Vec<SelectedService>,
+    profile: Option<&str>,
     project_path: PathBuf,
     target_path: impl Into<PathBuf>,
     deployment: bool,
+    features: &CargoFeatures,
     tx: tokio::sync::mpsc::Sender<String>,
 ) -> anyhow::Result<Vec<BuiltService>> {
     let manifest_path = project_path.join("Cargo.toml");
@@ -225,20 +378,25 @@ async fn compile(
         cmd.arg("--jobs=4");
     }
 
-    // TODO: Compile only one binary target in the package.
-    for package in &packages {
-        if package.features.contains_key("shuttle") {
-            cmd.arg("--no-default-features").arg("--features=shuttle");
+    for SelectedService { package, bin } in &services {
+        let CargoFeatures {
+            features,
+            no_default_features,
+        } = features.for_package(package);
+        if no_default_features {
+            cmd.arg("--no-default-features");
+        }
+        if !features.is_empty() {
+            cmd.arg(format!("--features={}", features.join(",")));
         }
         cmd.arg("--package").arg(package.name.as_str());
+        cmd.arg("--bin").arg(bin);
     }
 
-    let profile = if release_mode {
-        cmd.arg("--release");
-        "release"
-    } else {
-        "debug"
-    };
+    if let Some(profile) = profile {
+        cmd.arg("--profile").arg(profile);
+    }
+    let profile = profile_dir(profile);
 
     cmd.stderr(Stdio::piped());
     cmd.stdout(Stdio::null());
@@ -258,14 +416,14 @@ async fn compile(
         bail!("Build failed. Is the Shuttle runtime missing?");
     }
 
-    let services = packages
+    let services = services
         .iter()
-        .map(|package| {
+        .map(|SelectedService { package, bin }| {
             let mut path: PathBuf = [
                 project_path.clone(),
                 target_path.clone(),
                 profile.into(),
-                package.name.clone().into(),
+                bin.into(),
             ]
             .iter()
             .collect();
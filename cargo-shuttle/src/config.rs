@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
-use shuttle_common::constants::SHUTTLE_API_URL;
+use shuttle_common::{constants::SHUTTLE_API_URL, models::project::ComputeTier};
 use tracing::trace;
 
 use crate::args::ProjectArgs;
@@ -162,6 +162,8 @@ pub struct ProjectDeployConfig {
     pub include: Option<Vec<String>>,
     /// Set to true to deny deployments with uncommited changes. (use `--allow-dirty` to override)
     pub deny_dirty: Option<bool>,
+    /// Compute tier to run the project on. The project is updated to this tier before deploying.
+    pub compute_tier: Option<ComputeTier>,
 }
 /// Builder config
 #[derive(Deserialize, Serialize, Default)]
@@ -169,6 +171,21 @@ pub struct ProjectBuildConfig {
     /// Successor to `build_assets`.
     /// Patterns of files that should be copied from the build to the runtime container.
     pub assets: Option<Vec<String>>,
+    /// The package to build in a workspace with multiple Shuttle services. (use `--package` to override)
+    pub package: Option<String>,
+    /// The binary target to build in a package with multiple binaries. (use `--bin` to override)
+    pub bin: Option<String>,
+    /// Features to activate. (use `--features` to override)
+    /// Defaults to the `shuttle` feature without default features, if the package has one.
+    pub features: Option<Vec<String>>,
+    /// Set to true to not activate the default features. (use `--no-default-features` to override)
+    pub no_default_features: Option<bool>,
+    /// Set to true to use the mold linker in deployments. (use `--mold` to override)
+    pub mold: Option<bool>,
+    /// Set to false to not use cargo chef in deployments. (use `--no-cargo-chef` to override)
+    pub cargo_chef: Option<bool>,
+    /// Cargo profile to use in deployments. (use `--profile` to override)
+    pub profile: Option<String>,
 }
 
 /// .shuttle/config.toml schema (internal project-local config)
@@ -519,6 +536,43 @@ impl RequestContext {
             .and_then(|d| d.deny_dirty)
     }
 
+    /// # Panics
+    /// Panics if the project configuration has not been loaded.
+    pub fn compute_tier(&self) -> Option<ComputeTier> {
+        self.project
+            .as_ref()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .deploy
+            .as_ref()
+            .and_then(|d| d.compute_tier)
+    }
+
+    /// # Panics
+    /// Panics if the project configuration has not been loaded.
+    pub fn build_config(&self) -> Option<&ProjectBuildConfig> {
+        self.project
+            .as_ref()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .build
+            .as_ref()
+    }
+
+    /// # Panics
+    /// Panics if the project configuration has not been loaded.
+    pub fn build_package(&self) -> Option<&String> {
+        self.build_config().and_then(|b| b.package.as_ref())
+    }
+
+    /// # Panics
+    /// Panics if the project configuration has not been loaded.
+    pub fn build_bin(&self) -> Option<&String> {
+        self.build_config().and_then(|b| b.bin.as_ref())
+    }
+
     /// Check if the current project id has been loaded.
     pub fn project_id_found(&self) -> bool {
         self.project_internal
@@ -551,6 +605,8 @@ impl RequestContext {
 mod tests {
     use std::path::PathBuf;
 
+    use shuttle_common::models::project::ComputeTier;
+
     use crate::{args::ProjectArgs, config::RequestContext};
 
     use super::{Config, LocalConfigManager, ProjectConfig};
@@ -600,4 +656,21 @@ mod tests {
 
         assert_eq!(unwrap_project_name(&local_config), "my-fancy-project-name");
     }
+
+    #[test]
+    fn compute_tier_is_read_from_deploy_section() {
+        let config: ProjectConfig = toml::from_str(
+            r#"
+            [deploy]
+            compute_tier = "m"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.deploy.unwrap().compute_tier, Some(ComputeTier::M));
+
+        let config: ProjectConfig = toml::from_str("[deploy]\ndeny_dirty = true").unwrap();
+        assert_eq!(config.deploy.unwrap().compute_tier, None);
+
+        assert!(toml::from_str::<ProjectConfig>("[deploy]\ncompute_tier = \"huge\"").is_err());
+    }
 }
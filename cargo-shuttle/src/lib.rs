@@ -2,7 +2,11 @@ mod args;
 pub mod builder;
 pub mod config;
 mod init;
+#[cfg(feature = "migrate")]
+mod migrate;
+mod otel;
 mod provisioner_server;
+mod secrets;
 mod util;
 
 use std::collections::{BTreeMap, HashMap};
@@ -27,6 +31,7 @@ use ignore::WalkBuilder;
 use indicatif::ProgressBar;
 use indoc::formatdoc;
 use reqwest::header::HeaderMap;
+use serde::Serialize;
 use shuttle_api_client::ShuttleApiClient;
 use shuttle_common::{
     constants::{
@@ -37,15 +42,25 @@ use shuttle_common::{
         auth::{KeyMessage, TokenMessage},
         deployment::{
             BuildArgs, BuildArgsRust, BuildMeta, DeploymentRequest, DeploymentRequestBuildArchive,
-            DeploymentRequestImage, DeploymentResponse, DeploymentState, Environment,
-            GIT_STRINGS_MAX_LENGTH,
+            DeploymentRequestGitRepo, DeploymentRequestImage, DeploymentResponse, DeploymentState,
+            Environment, GIT_STRINGS_MAX_LENGTH,
         },
         error::ApiError,
-        log::LogItem,
-        project::ProjectUpdateRequest,
-        resource::ResourceType,
+        log::{LogItem, LogsQuery},
+        project::{ComputeTier, ProjectUpdateRequest},
+        resource::{ResourceResponse, ResourceType},
+        runtime::{RuntimeExitReason, RuntimeExitReport},
+        team::{AddTeamMemberRequest, TeamRole},
+        telemetry::{
+            BetterstackConfig, DatadogConfig, GrafanaCloudConfig, OtlpConfig, TelemetrySinkConfig,
+            TelemetrySinkConfigDiscriminants,
+        },
+    },
+    secrets::Secret,
+    tables::{
+        deployments_table, get_certificates_table, get_projects_table, get_resource_tables,
+        get_secrets_list_table, get_team_members_table, get_teams_table, get_telemetry_table,
     },
-    tables::{deployments_table, get_certificates_table, get_projects_table, get_resource_tables},
 };
 use strum::{EnumMessage, VariantArray};
 use tokio::io::{AsyncBufReadExt, BufReader};
@@ -56,20 +71,28 @@ use tracing_subscriber::{fmt, prelude::*, registry, EnvFilter};
 use zip::write::FileOptions;
 
 use crate::args::{
-    CertificateCommand, ConfirmationArgs, DeployArgs, DeploymentCommand, GenerateCommand, InitArgs,
-    LoginArgs, LogoutArgs, LogsArgs, ProjectCommand, ProjectUpdateCommand, ResourceCommand,
-    SecretsArgs, TableArgs, TemplateLocation,
+    CargoFeatureArgs, CertificateCommand, ConfirmationArgs, DeployArgs, DeploymentCommand,
+    GenerateCommand, InitArgs, LoginArgs, LogoutArgs, LogsArgs, OutputMode, ProjectCommand,
+    ProjectUpdateCommand, ResourceCommand, SecretsArgs, SecretsCommand, ServiceSelectionArgs,
+    TableArgs, TeamArgs, TeamCommand, TelemetryCommand, TelemetrySink, TemplateLocation,
 };
 pub use crate::args::{Command, ProjectArgs, RunArgs, ShuttleArgs};
-use crate::builder::{async_cargo_metadata, build_workspace, find_shuttle_packages, BuiltService};
+use crate::builder::{
+    async_cargo_metadata, build_workspace, find_shuttle_packages, select_shuttle_service,
+    BuiltService, CargoFeatures, ServiceSelection,
+};
 use crate::config::RequestContext;
+use crate::otel::OtelCollector;
 use crate::provisioner_server::{ProvApiState, ProvisionerServer};
+use crate::secrets::SecretDiff;
 use crate::util::{
     bacon, check_and_warn_runtime_version, generate_completions, generate_manpage,
     get_templates_schema, is_dirty, open_gh_issue, read_ws_until_text, update_cargo_shuttle,
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// How long a local run waits for a service to pass its readiness probe before warning about it
+const LOCAL_READY_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Returns the args and whether the PATH arg of the init command was explicitly given
 pub fn parse_args() -> (ShuttleArgs, bool) {
@@ -121,6 +144,7 @@ pub struct Shuttle {
     client: Option<ShuttleApiClient>,
     /// Alter behaviour based on which CLI is used
     bin: Binary,
+    output_mode: OutputMode,
 }
 
 impl Shuttle {
@@ -130,6 +154,7 @@ impl Shuttle {
             ctx,
             client: None,
             bin,
+            output_mode: OutputMode::Normal,
         })
     }
 
@@ -137,6 +162,7 @@ impl Shuttle {
         if matches!(args.cmd, Command::Resource(ResourceCommand::Dump { .. })) {
             bail!("This command is not yet supported on the NEW platform (shuttle.dev).");
         }
+        self.output_mode = args.output_mode;
 
         // All commands that call the API
         if matches!(
@@ -150,6 +176,9 @@ impl Shuttle {
                 | Command::Deployment(..)
                 | Command::Resource(..)
                 | Command::Certificate(..)
+                | Command::Secrets(..)
+                | Command::Team(..)
+                | Command::Telemetry(..)
                 | Command::Project(..)
         ) || (
             // project linking on beta requires api client
@@ -191,6 +220,8 @@ impl Shuttle {
                 | Command::Deployment(..)
                 | Command::Resource(..)
                 | Command::Certificate(..)
+                | Command::Secrets(..)
+                | Command::Telemetry(..)
                 | Command::Project(
                     // ProjectCommand::List does not need to know which project we are in
                     ProjectCommand::Create
@@ -257,6 +288,16 @@ impl Shuttle {
                     resource_type,
                     confirmation: ConfirmationArgs { yes },
                 } => self.resource_delete(&resource_type, yes).await,
+                #[cfg(feature = "migrate")]
+                ResourceCommand::Migrate {
+                    resource_type,
+                    source,
+                    dry_run,
+                    table,
+                } => {
+                    self.resource_migrate(&resource_type, &source, dry_run, table)
+                        .await
+                }
                 ResourceCommand::Dump { resource_type } => self.resource_dump(&resource_type).await,
             },
             Command::Certificate(cmd) => match cmd {
@@ -267,10 +308,52 @@ impl Shuttle {
                     confirmation: ConfirmationArgs { yes },
                 } => self.delete_certificate(domain, yes).await,
             },
+            Command::Secrets(cmd) => match cmd {
+                SecretsCommand::List { show_values, table } => {
+                    self.secrets_list(table, show_values).await
+                }
+                SecretsCommand::Set { secrets } => self.secrets_set(secrets).await,
+                SecretsCommand::Unset {
+                    keys,
+                    confirmation: ConfirmationArgs { yes },
+                } => self.secrets_unset(keys, yes).await,
+                SecretsCommand::Import { file } => self.secrets_import(file).await,
+                SecretsCommand::Export => self.secrets_export().await,
+                SecretsCommand::Diff { secret_args } => self.secrets_diff(secret_args).await,
+            },
+            Command::Team(cmd) => match cmd {
+                TeamCommand::List { table } => self.teams_list(table).await,
+                TeamCommand::Members { team, table } => self.team_members(team, table).await,
+                TeamCommand::Invite { user, role, team } => {
+                    self.team_invite(team, user, role).await
+                }
+                TeamCommand::Remove {
+                    user_id,
+                    team,
+                    confirmation: ConfirmationArgs { yes },
+                } => self.team_remove(team, user_id, yes).await,
+                TeamCommand::SetRole {
+                    user_id,
+                    role,
+                    team,
+                } => self.team_set_role(team, user_id, role).await,
+            },
+            Command::Telemetry(cmd) => match cmd {
+                TelemetryCommand::List { table } => self.telemetry_list(table).await,
+                TelemetryCommand::Enable { sink } => self.telemetry_enable(sink).await,
+                TelemetryCommand::Disable { sink } => self.telemetry_disable(sink).await,
+            },
             Command::Project(cmd) => match cmd {
                 ProjectCommand::Create => self.project_create().await,
                 ProjectCommand::Update(cmd) => match cmd {
                     ProjectUpdateCommand::Name { name } => self.project_rename(name).await,
+                    ProjectUpdateCommand::Compute { tier } => self.project_compute(tier).await,
+                    ProjectUpdateCommand::Transfer {
+                        team,
+                        user,
+                        personal,
+                        confirmation: ConfirmationArgs { yes },
+                    } => self.project_transfer(team, user, personal, yes).await,
                 },
                 ProjectCommand::Status => self.project_status().await,
                 ProjectCommand::List { table, .. } => self.projects_list(table).await,
@@ -741,6 +824,9 @@ impl Shuttle {
     async fn account(&self) -> Result<()> {
         let client = self.client.as_ref().unwrap();
         let user = client.get_current_user().await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&user);
+        }
         print!("{}", user.to_string_colored());
 
         Ok(())
@@ -910,46 +996,67 @@ impl Shuttle {
     }
 
     async fn logs(&self, args: LogsArgs) -> Result<()> {
-        if args.follow {
-            eprintln!("Streamed logs are not yet supported on the shuttle.dev platform.");
-            return Ok(());
-        }
-        if args.tail.is_some() | args.head.is_some() {
-            eprintln!("Fetching log ranges are not yet supported on the shuttle.dev platform.");
-            return Ok(());
-        }
+        let query = args.query();
         let client = self.client.as_ref().unwrap();
         let pid = self.ctx.project_id();
-        let logs = if args.all_deployments {
-            client.get_project_logs(pid).await?.logs
+        let id = if args.all_deployments {
+            None
+        } else if args.latest {
+            // Find latest deployment (not always an active one)
+            let deployments = client.get_deployments(pid, 1, 1).await?.deployments;
+            let Some(most_recent) = deployments.into_iter().next() else {
+                return self.no_deployments_for_logs(args.follow);
+            };
+            eprintln!("Getting logs from: {}", most_recent.id);
+            Some(most_recent.id)
+        } else if let Some(id) = args.id {
+            Some(id)
         } else {
-            let id = if args.latest {
-                // Find latest deployment (not always an active one)
-                let deployments = client.get_deployments(pid, 1, 1).await?.deployments;
-                let Some(most_recent) = deployments.into_iter().next() else {
-                    println!("No deployments found");
-                    return Ok(());
-                };
-                eprintln!("Getting logs from: {}", most_recent.id);
-                most_recent.id
-            } else if let Some(id) = args.id {
-                id
-            } else {
-                let Some(current) = client.get_current_deployment(pid).await? else {
-                    println!("No deployments found");
-                    return Ok(());
-                };
-                eprintln!("Getting logs from: {}", current.id);
-                current.id
+            let Some(current) = client.get_current_deployment(pid).await? else {
+                return self.no_deployments_for_logs(args.follow);
             };
-            client.get_deployment_logs(pid, &id).await?.logs
+            eprintln!("Getting logs from: {}", current.id);
+            Some(current.id)
         };
-        for log in logs {
-            if args.raw {
-                println!("{}", log.line);
-            } else {
-                println!("{log}");
+
+        if args.follow {
+            let mut stream = match id {
+                Some(id) => client.stream_deployment_logs(pid, &id, query),
+                None => client.stream_project_logs(pid, query),
+            };
+            while let Some(log) = stream.next().await {
+                let log = log?;
+                match self.output_mode {
+                    OutputMode::Normal => print_log(&log, args.raw),
+                    // one object per line, since the stream has no end to close an array at
+                    OutputMode::Json | OutputMode::Ndjson => {
+                        println!("{}", serde_json::to_string(&log)?)
+                    }
+                }
             }
+
+            return Ok(());
+        }
+
+        let logs = match id {
+            Some(id) => client.get_deployment_logs(pid, &id, &query).await?.logs,
+            None => client.get_project_logs(pid, &query).await?.logs,
+        };
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json_list(&logs);
+        }
+        for log in logs {
+            print_log(&log, args.raw);
+        }
+
+        Ok(())
+    }
+
+    /// Keep stdout parseable in JSON modes when there is no deployment to get logs from
+    fn no_deployments_for_logs(&self, follow: bool) -> Result<()> {
+        eprintln!("No deployments found");
+        if self.output_mode != OutputMode::Normal && !follow {
+            return self.print_json_list::<LogItem>(&[]);
         }
 
         Ok(())
@@ -975,6 +1082,9 @@ impl Shuttle {
         } else {
             false
         };
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json_list(&deployments);
+        }
         let table = deployments_table(&deployments, table_args.raw);
 
         println!(
@@ -1007,6 +1117,30 @@ impl Shuttle {
 
         println!("{}", deployment.to_string_colored());
 
+        if deployment.state == DeploymentState::Failed {
+            // explain why the runtime exited, if it reported it
+            let query = LogsQuery {
+                tail: Some(50),
+                ..Default::default()
+            };
+            match client
+                .get_deployment_logs(pid, &deployment.id, &query)
+                .await
+            {
+                Ok(logs) => {
+                    if let Some(report) = logs
+                        .logs
+                        .iter()
+                        .rev()
+                        .find_map(|log| RuntimeExitReport::from_line(&log.line))
+                    {
+                        println!("{}", runtime_exit_diagnostic(&report));
+                    }
+                }
+                Err(e) => debug!(error = ?e, "failed to get logs of failed deployment"),
+            }
+        }
+
         Ok(())
     }
 
@@ -1044,7 +1178,13 @@ impl Shuttle {
     async fn resources_list(&self, table_args: TableArgs, show_secrets: bool) -> Result<()> {
         let client = self.client.as_ref().unwrap();
         let pid = self.ctx.project_id();
-        let resources = client.get_service_resources(pid).await?.resources;
+        let mut resources = client.get_service_resources(pid).await?.resources;
+        if self.output_mode != OutputMode::Normal {
+            if !show_secrets {
+                resources.iter_mut().for_each(redact_resource_output);
+            }
+            return self.print_json_list(&resources);
+        }
         let table = get_resource_tables(resources.as_slice(), pid, table_args.raw, show_secrets);
 
         println!("{table}");
@@ -1096,6 +1236,54 @@ impl Shuttle {
         Ok(())
     }
 
+    #[cfg(feature = "migrate")]
+    async fn resource_migrate(
+        &self,
+        resource_type: &ResourceType,
+        source: &Path,
+        dry_run: bool,
+        table_args: TableArgs,
+    ) -> Result<()> {
+        if !matches!(
+            resource_type,
+            ResourceType::DatabaseSharedPostgres
+                | ResourceType::DatabaseAwsRdsPostgres
+                | ResourceType::DatabaseAwsRdsMySql
+                | ResourceType::DatabaseAwsRdsMariaDB
+        ) {
+            bail!("Migrations can only be run against SQL databases, not {resource_type}");
+        }
+
+        let client = self.client.as_ref().unwrap();
+        let resource = client
+            .get_service_resources(self.ctx.project_id())
+            .await?
+            .resources
+            .into_iter()
+            .find(|r| &r.r#type == resource_type)
+            .with_context(|| format!("This project has no {resource_type} resource"))?;
+        let info = serde_json::from_value::<shuttle_common::DatabaseInfo>(resource.output)
+            .context("resource data to be a valid database")?;
+
+        let dir = self.ctx.working_directory().join(source);
+        let migrations =
+            migrate::run_migrations(&info.connection_string(true), &dir, dry_run).await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json_list(&migrations);
+        }
+
+        if migrations.is_empty() {
+            println!("No migrations found in {}", dir.display());
+            return Ok(());
+        }
+        println!(
+            "{}",
+            migrate::get_migrations_table(&migrations, table_args.raw)
+        );
+
+        Ok(())
+    }
+
     async fn resource_dump(&self, _resource_type: &ResourceType) -> Result<()> {
         unimplemented!();
         // let client = self.client.as_ref().unwrap();
@@ -1110,6 +1298,9 @@ impl Shuttle {
             .list_certificates(self.ctx.project_id())
             .await?
             .certificates;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json_list(&certs);
+        }
 
         let table = get_certificates_table(certs.as_ref(), table_args.raw);
         println!("{}", table);
@@ -1159,42 +1350,210 @@ impl Shuttle {
         Ok(())
     }
 
+    /// Get the current secrets of the project from the API
+    async fn fetch_secrets(&self) -> Result<BTreeMap<String, String>> {
+        let client = self.client.as_ref().unwrap();
+        let secrets = client.get_secrets(self.ctx.project_id()).await?;
+        if secrets.output.is_null() {
+            return Ok(BTreeMap::new());
+        }
+
+        serde_json::from_value(secrets.output).context("parsing secrets")
+    }
+
+    async fn secrets_list(&self, table_args: TableArgs, show_values: bool) -> Result<()> {
+        let mut secrets = self.fetch_secrets().await?;
+        if self.output_mode != OutputMode::Normal {
+            if !show_values {
+                secrets
+                    .values_mut()
+                    .for_each(|value| *value = "********".to_owned());
+            }
+            return self.print_json(&secrets);
+        }
+        if secrets.is_empty() {
+            println!("No secrets are set for this project");
+            return Ok(());
+        }
+
+        println!(
+            "{}",
+            get_secrets_list_table(&secrets, table_args.raw, show_values)
+        );
+        if !show_values {
+            println!("Hint: you can show the values using `shuttle secrets list --show-values`");
+        }
+
+        Ok(())
+    }
+
+    async fn secrets_set(&self, args: Vec<String>) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let mut secrets = BTreeMap::new();
+        for arg in args {
+            let (key, value) = match arg.split_once('=') {
+                Some((key, value)) => (key.to_owned(), value.to_owned()),
+                // prompt for the value so that it does not end up in the shell history
+                None => {
+                    let value = Password::with_theme(&ColorfulTheme::default())
+                        .with_prompt(format!("Value of {arg}"))
+                        .allow_empty_password(true)
+                        .interact()?;
+                    (arg, value)
+                }
+            };
+            if key.is_empty() {
+                bail!("Secret keys can not be empty");
+            }
+            secrets.insert(key, value);
+        }
+
+        let keys = secrets.keys().cloned().collect::<Vec<_>>();
+        client.set_secrets(self.ctx.project_id(), secrets).await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&serde_json::json!({ "set": keys }));
+        }
+        println!("Set secrets: {}", keys.join(", "));
+        print_secrets_redeploy_note();
+
+        Ok(())
+    }
+
+    async fn secrets_unset(&self, keys: Vec<String>, no_confirm: bool) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+
+        if !no_confirm {
+            eprintln!(
+                "{}",
+                formatdoc!(
+                    "
+                WARNING:
+                    Remove the secrets {} from this project?
+                    This action is permanent.",
+                    keys.join(", ")
+                )
+                .bold()
+                .red()
+            );
+            if !Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Are you sure?")
+                .default(false)
+                .interact()
+                .unwrap()
+            {
+                return Ok(());
+            }
+        }
+
+        client
+            .unset_secrets(self.ctx.project_id(), keys.clone())
+            .await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&serde_json::json!({ "removed": keys }));
+        }
+        println!("Removed secrets: {}", keys.join(", "));
+        print_secrets_redeploy_note();
+
+        Ok(())
+    }
+
+    async fn secrets_import(&self, file: PathBuf) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let content = if file.as_os_str() == "-" {
+            std::io::read_to_string(std::io::stdin()).context("reading secrets from stdin")?
+        } else {
+            read_to_string(&file)
+                .with_context(|| format!("reading secrets file {}", file.display()))?
+        };
+        // flattened the same way as the secrets files used by `deploy` and `run`
+        let secrets = secrets::parse_secrets(&content).context("parsing secrets file")?;
+        let keys = secrets.keys().cloned().collect::<Vec<_>>();
+        if !secrets.is_empty() {
+            client.set_secrets(self.ctx.project_id(), secrets).await?;
+        }
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&serde_json::json!({ "imported": keys }));
+        }
+        if keys.is_empty() {
+            println!("No secrets to import");
+            return Ok(());
+        }
+        println!("Imported {} secrets", keys.len());
+        print_secrets_redeploy_note();
+
+        Ok(())
+    }
+
+    async fn secrets_diff(&self, secret_args: SecretsArgs) -> Result<()> {
+        let to_secrets = |secrets: BTreeMap<String, String>| {
+            secrets
+                .into_iter()
+                .map(|(k, v)| (k, Secret::new(v)))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let local = to_secrets(
+            Shuttle::get_secrets(&secret_args, self.ctx.working_directory(), false)?
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+        );
+        let deployed = to_secrets(self.fetch_secrets().await?);
+        let diff = secrets::diff_secrets(&local, &deployed);
+
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json_list(&diff);
+        }
+        if diff.is_empty() {
+            println!("Local and deployed secrets are the same");
+            return Ok(());
+        }
+        for change in diff {
+            let line = match change {
+                SecretDiff::Added(ref key) => {
+                    format!("+ {key} = {} (only local)", local[key].redacted()).green()
+                }
+                SecretDiff::Removed(ref key) => {
+                    format!("- {key} = {} (only deployed)", deployed[key].redacted()).red()
+                }
+                SecretDiff::Changed(ref key) => {
+                    format!("~ {key} = {} (differs)", local[key].redacted()).yellow()
+                }
+            };
+            println!("{line}");
+        }
+
+        Ok(())
+    }
+
+    async fn secrets_export(&self) -> Result<()> {
+        let secrets = self.fetch_secrets().await?;
+        print!("{}", toml::to_string(&secrets)?);
+
+        Ok(())
+    }
+
+    /// Resolve the local secrets from secrets files and the environment, see [`secrets::resolve_secrets`].
+    /// In local runs (`dev`), overrides from Secrets.dev.toml are applied unless another environment is given.
     fn get_secrets(
         args: &SecretsArgs,
         workspace_root: &Path,
         dev: bool,
     ) -> Result<Option<HashMap<String, String>>> {
-        // Look for a secrets file, first in the command args, then in the root of the workspace.
-        let files: &[PathBuf] = if dev {
-            &[
-                workspace_root.join("Secrets.dev.toml"),
-                workspace_root.join("Secrets.toml"),
-            ]
-        } else {
-            &[workspace_root.join("Secrets.toml")]
-        };
-        let secrets_file = args.secrets.as_ref().or_else(|| {
-            files
-                .iter()
-                .find(|&secrets_file| secrets_file.exists() && secrets_file.is_file())
-        });
-
-        Ok(if let Some(secrets_file) = secrets_file {
-            trace!("Loading secrets from {}", secrets_file.display());
-            if let Ok(secrets_str) = read_to_string(secrets_file) {
-                let secrets = toml::from_str::<HashMap<String, String>>(&secrets_str)?;
-
-                trace!(keys = ?secrets.keys(), "available secrets");
+        let env = args
+            .secrets_env
+            .as_deref()
+            .or(if dev { Some("dev") } else { None });
+        let secrets = secrets::resolve_secrets(
+            workspace_root,
+            args.secrets.as_deref(),
+            env,
+            std::env::vars(),
+        )?;
+        if secrets.is_none() {
+            trace!("No secrets were found");
+        }
 
-                Some(secrets)
-            } else {
-                trace!("No secrets were loaded");
-                None
-            }
-        } else {
-            trace!("No secrets file was found");
-            None
-        })
+        Ok(secrets.map(|s| s.into_iter().collect()))
     }
 
     async fn pre_local_run(&self, run_args: &RunArgs) -> Result<Vec<BuiltService>> {
@@ -1215,28 +1574,75 @@ impl Shuttle {
             working_directory.display()
         );
 
-        build_workspace(working_directory, run_args.release, tx, false).await
+        let profile = run_args
+            .profile
+            .as_deref()
+            .or(run_args.release.then_some("release"));
+        build_workspace(
+            working_directory,
+            profile,
+            tx,
+            false,
+            &ServiceSelection {
+                all: run_args.all,
+                ..self.service_selection(&run_args.service_args)
+            },
+            &self.cargo_features(&run_args.feature_args),
+        )
+        .await
+    }
+
+    /// Which service to build, from the command line args or Shuttle.toml
+    fn service_selection(&self, args: &ServiceSelectionArgs) -> ServiceSelection {
+        ServiceSelection {
+            package: args
+                .package
+                .clone()
+                .or_else(|| self.ctx.build_package().cloned()),
+            bin: args.bin.clone().or_else(|| self.ctx.build_bin().cloned()),
+            ..Default::default()
+        }
+    }
+
+    /// Which cargo features to build with, from the command line args or Shuttle.toml
+    fn cargo_features(&self, args: &CargoFeatureArgs) -> CargoFeatures {
+        let build_config = self.ctx.build_config();
+        CargoFeatures {
+            features: if args.features.is_empty() {
+                build_config
+                    .and_then(|b| b.features.clone())
+                    .unwrap_or_default()
+            } else {
+                args.features.clone()
+            },
+            no_default_features: args
+                .no_default_features_override()
+                .or_else(|| build_config.and_then(|b| b.no_default_features))
+                .unwrap_or_default(),
+        }
     }
 
-    fn find_available_port(run_args: &mut RunArgs) {
-        let original_port = run_args.port;
-        for port in (run_args.port..=u16::MAX).step_by(10) {
+    fn find_available_port(original_port: u16) -> u16 {
+        let mut available_port = original_port;
+        for port in (original_port..=u16::MAX).step_by(10) {
             if !portpicker::is_free_tcp(port) {
                 continue;
             }
-            run_args.port = port;
+            available_port = port;
             break;
         }
 
-        if run_args.port != original_port {
+        if available_port != original_port {
             eprintln!(
                 "Port {} is already in use. Using port {}.",
-                original_port, run_args.port,
+                original_port, available_port,
             )
         };
+
+        available_port
     }
 
-    async fn local_run(&self, mut run_args: RunArgs, debug: bool) -> Result<()> {
+    async fn local_run(&self, run_args: RunArgs, debug: bool) -> Result<()> {
         let project_name = self.ctx.project_name().to_owned();
         let working_directory = self.ctx.working_directory();
 
@@ -1251,126 +1657,70 @@ impl Shuttle {
         }
 
         let services = self.pre_local_run(&run_args).await?;
-        let service = services
-            .first()
-            .expect("at least one shuttle service")
-            .to_owned();
-
-        trace!(path = ?service.executable_path, "runtime executable");
 
-        let secrets = Shuttle::get_secrets(&run_args.secret_args, working_directory, true)?
-            .unwrap_or_default();
-        Shuttle::find_available_port(&mut run_args);
-        if let Some(warning) = check_and_warn_runtime_version(&service.executable_path).await? {
-            eprint!("{}", warning);
+        if run_args.otel_args.otel {
+            let args = &run_args.otel_args;
+            OtelCollector::new(args.otel_file.as_deref())?
+                .start(
+                    SocketAddr::new(Ipv4Addr::LOCALHOST.into(), args.otel_grpc_port),
+                    SocketAddr::new(Ipv4Addr::LOCALHOST.into(), args.otel_http_port),
+                )
+                .await?;
+            println!(
+                "\n    {} OpenTelemetry collector on http://127.0.0.1:{} (HTTP) and http://127.0.0.1:{} (gRPC)",
+                "Started".bold().green(),
+                args.otel_http_port,
+                args.otel_grpc_port,
+            );
+            if let Some(file) = &args.otel_file {
+                println!("    Writing received telemetry to {}", file.display());
+            }
         }
 
-        let runtime_executable = service.executable_path.clone();
-        let api_port = portpicker::pick_unused_port()
-            .expect("failed to find available port for local provisioner server");
-        let api_addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), api_port);
-        let healthz_port = portpicker::pick_unused_port()
-            .expect("failed to find available port for runtime health check");
-        let ip = if run_args.external {
-            Ipv4Addr::UNSPECIFIED
-        } else {
-            Ipv4Addr::LOCALHOST
-        };
-
-        let state = Arc::new(ProvApiState {
-            project_name: project_name.clone(),
-            secrets,
-        });
-        tokio::spawn(async move { ProvisionerServer::run(state, &api_addr).await });
-
-        println!(
-            "\n    {} {} on http://{}:{}\n",
-            "Starting".bold().green(),
-            service.package_name,
-            ip,
-            run_args.port,
-        );
-
-        let mut envs = vec![
-            ("SHUTTLE_BETA", "true".to_owned()),
-            ("SHUTTLE_PROJECT_ID", "proj_LOCAL".to_owned()),
-            ("SHUTTLE_PROJECT_NAME", project_name),
-            ("SHUTTLE_ENV", Environment::Local.to_string()),
-            ("SHUTTLE_RUNTIME_IP", ip.to_string()),
-            ("SHUTTLE_RUNTIME_PORT", run_args.port.to_string()),
-            ("SHUTTLE_HEALTHZ_PORT", healthz_port.to_string()),
-            ("SHUTTLE_API", format!("http://127.0.0.1:{}", api_port)),
-        ];
-        // Use a nice debugging tracing level if user does not provide their own
-        if debug && std::env::var("RUST_LOG").is_err() {
-            envs.push(("RUST_LOG", "info,shuttle=trace,reqwest=debug".to_owned()));
+        let mut runtimes = Vec::new();
+        let mut port = run_args.port;
+        for service in &services {
+            port = Shuttle::find_available_port(port);
+            let runtime = if run_args.all {
+                // each service gets its own secrets, provisioner state, and log prefix
+                let service_name = service.service_name()?;
+                let secrets_env = run_args.secret_args.secrets_env.as_deref().or(Some("dev"));
+                let secrets_root =
+                    if secrets::has_secrets_file(service.crate_directory(), secrets_env) {
+                        service.crate_directory()
+                    } else {
+                        working_directory
+                    };
+                let secrets = Shuttle::get_secrets(&run_args.secret_args, secrets_root, true)?
+                    .unwrap_or_default();
+                Shuttle::spawn_runtime(
+                    service,
+                    service_name.clone(),
+                    service_name,
+                    secrets,
+                    &run_args,
+                    port,
+                    debug,
+                )
+                .await?
+            } else {
+                let secrets = Shuttle::get_secrets(&run_args.secret_args, working_directory, true)?
+                    .unwrap_or_default();
+                Shuttle::spawn_runtime(
+                    service,
+                    project_name.clone(),
+                    "app".to_owned(),
+                    secrets,
+                    &run_args,
+                    port,
+                    debug,
+                )
+                .await?
+            };
+            runtimes.push((service.package_name.clone(), runtime));
+            port += 1;
         }
 
-        info!(
-            path = %runtime_executable.display(),
-            "Spawning runtime process",
-        );
-        let mut runtime = tokio::process::Command::new(
-            dunce::canonicalize(runtime_executable).context("canonicalize path of executable")?,
-        )
-        .current_dir(&service.workspace_path)
-        .envs(envs)
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .kill_on_drop(true)
-        .spawn()
-        .context("spawning runtime process")?;
-
-        // Start background tasks for reading runtime's stdout and stderr
-        let raw = run_args.raw;
-        let mut stdout_reader = BufReader::new(
-            runtime
-                .stdout
-                .take()
-                .context("child process did not have a handle to stdout")?,
-        )
-        .lines();
-        tokio::spawn(async move {
-            while let Some(line) = stdout_reader.next_line().await.unwrap() {
-                if raw {
-                    println!("{}", line);
-                } else {
-                    let log_item = LogItem::new(Utc::now(), "app".to_owned(), line);
-                    println!("{log_item}");
-                }
-            }
-        });
-        let mut stderr_reader = BufReader::new(
-            runtime
-                .stderr
-                .take()
-                .context("child process did not have a handle to stderr")?,
-        )
-        .lines();
-        tokio::spawn(async move {
-            while let Some(line) = stderr_reader.next_line().await.unwrap() {
-                if raw {
-                    println!("{}", line);
-                } else {
-                    let log_item = LogItem::new(Utc::now(), "app".to_owned(), line);
-                    println!("{log_item}");
-                }
-            }
-        });
-
-        // Start background task for simulated health check
-        tokio::spawn(async move {
-            loop {
-                // ECS health check runs ever 5s
-                tokio::time::sleep(tokio::time::Duration::from_millis(5000)).await;
-
-                tracing::trace!("Health check against runtime");
-                if let Err(e) = reqwest::get(format!("http://127.0.0.1:{}/", healthz_port)).await {
-                    tracing::trace!("Health check against runtime failed: {e}");
-                }
-            }
-        });
-
         #[cfg(target_family = "unix")]
         let exit_result = {
             let mut sigterm_notif =
@@ -1380,7 +1730,7 @@ impl Shuttle {
                 tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt())
                     .expect("Can not get the SIGINT signal receptor");
             tokio::select! {
-                exit_result = runtime.wait() => {
+                exit_result = wait_for_any_runtime(&mut runtimes) => {
                     Some(exit_result)
                 }
                 _ = sigterm_notif.recv() => {
@@ -1406,7 +1756,7 @@ impl Shuttle {
             let mut ctrl_shutdown_notif = tokio::signal::windows::ctrl_shutdown()
                 .expect("Can not get the CtrlShutdown signal receptor");
             tokio::select! {
-                exit_result = runtime.wait() => {
+                exit_result = wait_for_any_runtime(&mut runtimes) => {
                     Some(exit_result)
                 }
                 _ = ctrl_break_notif.recv() => {
@@ -1431,28 +1781,192 @@ impl Shuttle {
                 }
             }
         };
+        // shut down all services together
+        for (_, runtime) in runtimes.iter_mut() {
+            // the runtime that exited on its own can not be killed
+            let _ = runtime.kill().await;
+        }
         match exit_result {
-            Some(Ok(exit_status)) => {
+            Some((name, Ok(exit_status))) => {
+                if services.len() > 1 {
+                    bail!(
+                        "Runtime process of {name} exited with code {}",
+                        exit_status.code().unwrap_or_default()
+                    );
+                }
                 bail!(
                     "Runtime process exited with code {}",
                     exit_status.code().unwrap_or_default()
                 );
             }
-            Some(Err(e)) => {
-                bail!("Failed to wait for runtime process to exit: {e}");
-            }
-            None => {
-                runtime.kill().await?;
+            Some((name, Err(e))) => {
+                bail!("Failed to wait for runtime process of {name} to exit: {e}");
             }
+            None => {}
         }
 
         Ok(())
     }
 
+    /// Start a local provisioner and the runtime process of a built service.
+    /// Log lines from the runtime are printed with `log_source` as the origin tag.
+    async fn spawn_runtime(
+        service: &BuiltService,
+        project_name: String,
+        log_source: String,
+        secrets: HashMap<String, String>,
+        run_args: &RunArgs,
+        port: u16,
+        debug: bool,
+    ) -> Result<tokio::process::Child> {
+        trace!(path = ?service.executable_path, "runtime executable");
+        let ip = if run_args.external {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+
+        if let Some(warning) = check_and_warn_runtime_version(&service.executable_path).await? {
+            eprint!("{}", warning);
+        }
+
+        let runtime_executable = service.executable_path.clone();
+        let api_port = portpicker::pick_unused_port()
+            .expect("failed to find available port for local provisioner server");
+        let api_addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), api_port);
+        let healthz_port = portpicker::pick_unused_port()
+            .expect("failed to find available port for runtime health check");
+
+        let state = Arc::new(ProvApiState {
+            project_name: project_name.clone(),
+            secrets,
+        });
+        tokio::spawn(async move { ProvisionerServer::run(state, &api_addr).await });
+
+        println!(
+            "\n    {} {} on http://{}:{}\n",
+            "Starting".bold().green(),
+            service.package_name,
+            ip,
+            port,
+        );
+        let package_name = service.package_name.clone();
+
+        let mut envs = vec![
+            ("SHUTTLE_BETA", "true".to_owned()),
+            ("SHUTTLE_PROJECT_ID", "proj_LOCAL".to_owned()),
+            ("SHUTTLE_PROJECT_NAME", project_name),
+            ("SHUTTLE_ENV", Environment::Local.to_string()),
+            ("SHUTTLE_RUNTIME_IP", ip.to_string()),
+            ("SHUTTLE_RUNTIME_PORT", port.to_string()),
+            ("SHUTTLE_HEALTHZ_PORT", healthz_port.to_string()),
+            ("SHUTTLE_API", format!("http://127.0.0.1:{}", api_port)),
+        ];
+        if run_args.otel_args.otel {
+            envs.push((
+                "OTEL_EXPORTER_OTLP_ENDPOINT",
+                format!("http://127.0.0.1:{}", run_args.otel_args.otel_http_port),
+            ));
+        }
+        // Use a nice debugging tracing level if user does not provide their own
+        if debug && std::env::var("RUST_LOG").is_err() {
+            envs.push(("RUST_LOG", "info,shuttle=trace,reqwest=debug".to_owned()));
+        }
+
+        info!(
+            path = %runtime_executable.display(),
+            "Spawning runtime process",
+        );
+        let mut runtime = tokio::process::Command::new(
+            dunce::canonicalize(runtime_executable).context("canonicalize path of executable")?,
+        )
+        .current_dir(&service.workspace_path)
+        .envs(envs)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("spawning runtime process")?;
+
+        // Start background tasks for reading runtime's stdout and stderr
+        let raw = run_args.raw;
+        let mut stdout_reader = BufReader::new(
+            runtime
+                .stdout
+                .take()
+                .context("child process did not have a handle to stdout")?,
+        )
+        .lines();
+        let source = log_source.clone();
+        tokio::spawn(async move {
+            while let Some(line) = stdout_reader.next_line().await.unwrap() {
+                print_runtime_line(&source, line, raw);
+            }
+        });
+        let mut stderr_reader = BufReader::new(
+            runtime
+                .stderr
+                .take()
+                .context("child process did not have a handle to stderr")?,
+        )
+        .lines();
+        tokio::spawn(async move {
+            while let Some(line) = stderr_reader.next_line().await.unwrap() {
+                print_runtime_line(&log_source, line, raw);
+            }
+        });
+
+        // Start background task for simulated health check
+        tokio::spawn(async move {
+            // the readiness probe passes once the service is listening
+            let ready_url = format!("http://127.0.0.1:{}/healthz/ready", healthz_port);
+            let wait_ready = async {
+                loop {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+                    match reqwest::get(&ready_url).await {
+                        Ok(res) if res.status().is_success() => break,
+                        Ok(res) => tracing::trace!("Service not ready yet: {}", res.status()),
+                        Err(e) => tracing::trace!("Readiness check against runtime failed: {e}"),
+                    }
+                }
+            };
+            if tokio::time::timeout(LOCAL_READY_TIMEOUT, wait_ready)
+                .await
+                .is_ok()
+            {
+                println!(
+                    "\n    {} {} is listening on http://{}:{}\n",
+                    "Ready".bold().green(),
+                    package_name,
+                    ip,
+                    port,
+                );
+            } else {
+                eprintln!(
+                    "WARNING: {} did not pass its readiness check within {} seconds. Make sure it listens on port {}.",
+                    package_name,
+                    LOCAL_READY_TIMEOUT.as_secs(),
+                    port,
+                );
+            }
+
+            loop {
+                // ECS health check runs ever 5s
+                tokio::time::sleep(tokio::time::Duration::from_millis(5000)).await;
+
+                tracing::trace!("Health check against runtime");
+                if let Err(e) = reqwest::get(format!("http://127.0.0.1:{}/", healthz_port)).await {
+                    tracing::trace!("Health check against runtime failed: {e}");
+                }
+            }
+        });
+
+        Ok(runtime)
+    }
+
     async fn deploy(&mut self, args: DeployArgs) -> Result<()> {
         let client = self.client.as_ref().unwrap();
         let working_directory = self.ctx.working_directory();
-        let manifest_path = working_directory.join("Cargo.toml");
 
         let secrets = Shuttle::get_secrets(&args.secret_args, working_directory, false)?;
 
@@ -1461,6 +1975,7 @@ impl Shuttle {
             let pid = self.ctx.project_id();
             let deployment_req_image = DeploymentRequestImage { image, secrets };
 
+            self.reconcile_compute_tier().await?;
             let deployment = client
                 .deploy(pid, DeploymentRequest::Image(deployment_req_image))
                 .await?;
@@ -1480,48 +1995,45 @@ impl Shuttle {
             return Ok(());
         }
 
+        // Git repository deployment mode
+        if let Some(ref repo_url) = args.git {
+            let pid = self.ctx.project_id();
+            let deployment_req_git = DeploymentRequestGitRepo {
+                repo_url: repo_url.clone(),
+                git_ref: args.git_ref.clone(),
+                subdirectory: args.subdirectory.clone(),
+                // the local workspace can be at a different revision than `--ref`,
+                // so the builder determines the build args from the checked out repository
+                build_args: None,
+                secrets,
+            };
+
+            self.reconcile_compute_tier().await?;
+            eprintln!("Creating deployment...");
+            let deployment = client.deploy_git_repo(pid, deployment_req_git).await?;
+
+            if args.tracking_args.no_follow {
+                println!("{}", deployment.to_string_colored());
+                return Ok(());
+            }
+
+            self.track_deployment_status_and_print_logs_on_fail(
+                pid,
+                &deployment.id,
+                args.tracking_args.raw,
+            )
+            .await?;
+
+            return Ok(());
+        }
+
         // Build archive deployment mode
         let mut deployment_req = DeploymentRequestBuildArchive {
             secrets,
+            build_args: Some(BuildArgs::Rust(self.rust_build_args(&args).await?)),
             ..Default::default()
         };
         let mut build_meta = BuildMeta::default();
-        let mut rust_build_args = BuildArgsRust::default();
-
-        let metadata = async_cargo_metadata(manifest_path.as_path()).await?;
-        let packages = find_shuttle_packages(&metadata)?;
-        // TODO: support overriding this
-        let package = packages
-            .first()
-            .expect("Expected at least one crate with shuttle-runtime in the workspace");
-        let package_name = package.name.to_owned();
-        rust_build_args.package_name = Some(package_name);
-
-        // activate shuttle feature if present
-        let (no_default_features, features) = if package.features.contains_key("shuttle") {
-            (true, Some(vec!["shuttle".to_owned()]))
-        } else {
-            (false, None)
-        };
-        rust_build_args.no_default_features = no_default_features;
-        rust_build_args.features = features.map(|v| v.join(","));
-
-        rust_build_args.shuttle_runtime_version = package
-            .dependencies
-            .iter()
-            .find(|dependency| dependency.name == RUNTIME_NAME)
-            .expect("shuttle package to have runtime dependency")
-            .req
-            .comparators
-            .first()
-            // is "^0.X.0" when `shuttle-runtime = "0.X.0"` is in Cargo.toml
-            .and_then(|c| c.to_string().strip_prefix('^').map(ToOwned::to_owned));
-
-        // TODO: determine which (one) binary to build
-
-        deployment_req.build_args = Some(BuildArgs::Rust(rust_build_args));
-
-        // TODO: have all of the above be configurable in CLI and Shuttle.toml
 
         if let Ok(repo) = Repository::discover(working_directory) {
             let repo_path = repo
@@ -1573,9 +2085,13 @@ impl Shuttle {
         deployment_req.archive_version_id = arch.archive_version_id;
         deployment_req.build_meta = Some(build_meta);
 
+        self.reconcile_compute_tier().await?;
         eprintln!("Creating deployment...");
         let deployment = client
-            .deploy(pid, DeploymentRequest::BuildArchive(deployment_req))
+            .deploy(
+                pid,
+                DeploymentRequest::BuildArchive(Box::new(deployment_req)),
+            )
             .await?;
 
         if args.tracking_args.no_follow {
@@ -1593,6 +2109,55 @@ impl Shuttle {
         Ok(())
     }
 
+    /// Determine how to build the selected service, from the local Cargo workspace and the args
+    async fn rust_build_args(&self, args: &DeployArgs) -> Result<BuildArgsRust> {
+        let manifest_path = self.ctx.working_directory().join("Cargo.toml");
+        let mut rust_build_args = BuildArgsRust::default();
+
+        let metadata = async_cargo_metadata(manifest_path.as_path()).await?;
+        let packages = find_shuttle_packages(&metadata)?;
+        let selected =
+            select_shuttle_service(&packages, &self.service_selection(&args.service_args))?;
+        let package = &selected.package;
+        rust_build_args.package_name = Some(package.name.to_owned());
+        rust_build_args.binary_name = Some(selected.bin);
+
+        // activates the shuttle feature if present and no features were configured
+        let CargoFeatures {
+            features,
+            no_default_features,
+        } = self.cargo_features(&args.feature_args).for_package(package);
+        rust_build_args.no_default_features = no_default_features;
+        rust_build_args.features = (!features.is_empty()).then(|| features.join(","));
+
+        let build_config = self.ctx.build_config();
+        rust_build_args.mold = args
+            .mold_override()
+            .or_else(|| build_config.and_then(|b| b.mold))
+            .unwrap_or_default();
+        rust_build_args.cargo_chef = args
+            .cargo_chef_override()
+            .or_else(|| build_config.and_then(|b| b.cargo_chef))
+            .unwrap_or(true);
+        rust_build_args.profile = args
+            .profile
+            .clone()
+            .or_else(|| build_config.and_then(|b| b.profile.clone()));
+
+        rust_build_args.shuttle_runtime_version = package
+            .dependencies
+            .iter()
+            .find(|dependency| dependency.name == RUNTIME_NAME)
+            .expect("shuttle package to have runtime dependency")
+            .req
+            .comparators
+            .first()
+            // is "^0.X.0" when `shuttle-runtime = "0.X.0"` is in Cargo.toml
+            .and_then(|c| c.to_string().strip_prefix('^').map(ToOwned::to_owned));
+
+        Ok(rust_build_args)
+    }
+
     /// Returns true if the deployment failed
     async fn track_deployment_status(&self, pid: &str, id: &str) -> Result<bool> {
         let client = self.client.as_ref().unwrap();
@@ -1630,18 +2195,211 @@ impl Shuttle {
     ) -> Result<()> {
         let client = self.client.as_ref().unwrap();
         if self.track_deployment_status(proj_id, depl_id).await? {
-            for log in client.get_deployment_logs(proj_id, depl_id).await?.logs {
-                if raw {
-                    println!("{}", log.line);
-                } else {
-                    println!("{log}");
-                }
+            for log in client
+                .get_deployment_logs(proj_id, depl_id, &Default::default())
+                .await?
+                .logs
+            {
+                print_log(&log, raw);
             }
         }
 
         Ok(())
     }
 
+    async fn telemetry_list(&self, table_args: TableArgs) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let config = client.get_telemetry_config(self.ctx.project_id()).await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&config);
+        }
+
+        println!("{}", get_telemetry_table(&config, table_args.raw));
+
+        Ok(())
+    }
+
+    async fn telemetry_enable(&self, sink: TelemetrySink) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let sink_config = prompt_telemetry_sink_config(sink)?;
+        let sink_name = sink_config.as_ref().to_owned();
+        let config = client
+            .set_telemetry_sink_config(self.ctx.project_id(), sink_config)
+            .await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&config);
+        }
+
+        println!("Enabled telemetry export to {sink_name}");
+        println!("{}", get_telemetry_table(&config, false));
+
+        Ok(())
+    }
+
+    async fn telemetry_disable(&self, sink: TelemetrySink) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let sink = TelemetrySinkConfigDiscriminants::from(sink);
+        let config = client
+            .delete_telemetry_sink_config(self.ctx.project_id(), sink)
+            .await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&config);
+        }
+
+        println!("Disabled telemetry export to {}", sink.as_ref());
+        println!("{}", get_telemetry_table(&config, false));
+
+        Ok(())
+    }
+
+    /// The given team, or the only team the user is a member of
+    async fn team_id(&self, team_args: TeamArgs) -> Result<String> {
+        if let Some(team_id) = team_args.team_id {
+            return Ok(team_id);
+        }
+
+        let client = self.client.as_ref().unwrap();
+        let teams = client.get_teams_list().await?.teams;
+        match teams.as_slice() {
+            [team] => Ok(team.id.clone()),
+            [] => bail!("You are not a member of any team"),
+            _ => bail!(
+                "You are a member of several teams. Use --team to choose one of the IDs from `shuttle team list`"
+            ),
+        }
+    }
+
+    async fn teams_list(&self, table_args: TableArgs) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let teams = client.get_teams_list().await?.teams;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json_list(&teams);
+        }
+
+        if teams.is_empty() {
+            println!("You are not a member of any team");
+        } else {
+            println!("{}", get_teams_table(&teams, table_args.raw));
+        }
+
+        Ok(())
+    }
+
+    async fn team_members(&self, team_args: TeamArgs, table_args: TableArgs) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let team_id = self.team_id(team_args).await?;
+        let members = client.get_team_members(&team_id).await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&members);
+        }
+
+        println!("{}", format!("Team {team_id} members").bold());
+        println!("{}", get_team_members_table(&members, table_args.raw));
+
+        Ok(())
+    }
+
+    async fn team_invite(&self, team_args: TeamArgs, user: String, role: TeamRole) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let team_id = self.team_id(team_args).await?;
+
+        // email addresses get an invite, user IDs are added directly
+        let (user_id, email) = if user.contains('@') {
+            (None, Some(user))
+        } else {
+            (Some(user), None)
+        };
+        let msg = client
+            .add_team_member(
+                &team_id,
+                AddTeamMemberRequest {
+                    user_id: user_id.clone(),
+                    email: email.clone(),
+                    role: Some(role.clone()),
+                },
+            )
+            .await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&serde_json::json!({
+                "team_id": team_id,
+                "user_id": user_id,
+                "email": email,
+                "role": role,
+                "message": msg,
+            }));
+        }
+        println!("{msg}");
+
+        Ok(())
+    }
+
+    async fn team_remove(
+        &self,
+        team_args: TeamArgs,
+        user_id: String,
+        no_confirm: bool,
+    ) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let team_id = self.team_id(team_args).await?;
+
+        if !no_confirm {
+            eprintln!(
+                "{}",
+                formatdoc!(
+                    "
+                WARNING:
+                    Remove {} from team {}?
+                    They will lose access to the team's projects.",
+                    user_id,
+                    team_id,
+                )
+                .bold()
+                .red()
+            );
+            if !Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Are you sure?")
+                .default(false)
+                .interact()
+                .unwrap()
+            {
+                return Ok(());
+            }
+        }
+
+        let msg = client.remove_team_member(&team_id, &user_id).await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&serde_json::json!({
+                "team_id": team_id,
+                "user_id": user_id,
+                "message": msg,
+            }));
+        }
+        println!("{msg}");
+
+        Ok(())
+    }
+
+    async fn team_set_role(
+        &self,
+        team_args: TeamArgs,
+        user_id: String,
+        role: TeamRole,
+    ) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let team_id = self.team_id(team_args).await?;
+        let member = client.update_team_member(&team_id, &user_id, role).await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&member);
+        }
+
+        println!(
+            "{} is now {} of team {}",
+            member.user_id, member.role, team_id
+        );
+
+        Ok(())
+    }
+
     async fn project_create(&self) -> Result<()> {
         let client = self.client.as_ref().unwrap();
         let name = self.ctx.project_name();
@@ -1669,9 +2427,149 @@ impl Shuttle {
         Ok(())
     }
 
+    async fn project_compute(&self, tier: ComputeTier) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+
+        let project = client
+            .update_project(
+                self.ctx.project_id(),
+                ProjectUpdateRequest {
+                    compute_tier: Some(tier),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&project);
+        }
+
+        println!(
+            "Set compute tier of project '{}' ({}) to {}",
+            project.name,
+            project.id,
+            project
+                .compute_tier
+                .map(|t| t.to_string())
+                .as_deref()
+                .unwrap_or("N/A")
+        );
+
+        Ok(())
+    }
+
+    /// Update the project to the compute tier declared in Shuttle.toml, if it differs
+    async fn reconcile_compute_tier(&self) -> Result<()> {
+        let Some(tier) = self.ctx.compute_tier() else {
+            return Ok(());
+        };
+        let client = self.client.as_ref().unwrap();
+        let pid = self.ctx.project_id();
+
+        let current = client.get_project(pid).await?.compute_tier;
+        if current == Some(tier) {
+            trace!(%tier, "compute tier is up to date");
+            return Ok(());
+        }
+
+        match current {
+            Some(current) => eprintln!("Changing compute tier from {current} to {tier}..."),
+            None => eprintln!("Setting compute tier to {tier}..."),
+        }
+        client
+            .update_project(
+                pid,
+                ProjectUpdateRequest {
+                    compute_tier: Some(tier),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Transfer the project to `team_id` or `user_id`, or out of its team if neither is given
+    async fn project_transfer(
+        &self,
+        team_id: Option<String>,
+        user_id: Option<String>,
+        personal: bool,
+        no_confirm: bool,
+    ) -> Result<()> {
+        let client = self.client.as_ref().unwrap();
+        let pid = self.ctx.project_id();
+
+        let (new_owner, request) = match (team_id, user_id, personal) {
+            (Some(team_id), None, false) => (
+                format!("team {team_id}"),
+                ProjectUpdateRequest {
+                    team_id: Some(team_id),
+                    ..Default::default()
+                },
+            ),
+            (None, Some(user_id), false) => (
+                format!("user {user_id}"),
+                ProjectUpdateRequest {
+                    user_id: Some(user_id),
+                    ..Default::default()
+                },
+            ),
+            (None, None, true) => (
+                "your personal account".to_owned(),
+                ProjectUpdateRequest {
+                    remove_from_team: Some(true),
+                    ..Default::default()
+                },
+            ),
+            _ => bail!("Specify exactly one of --team, --user or --personal"),
+        };
+
+        if !no_confirm {
+            // check that the project exists, and look up the name
+            let proj = client.get_project(pid).await?;
+            eprintln!(
+                "{}",
+                formatdoc!(
+                    r#"
+                    WARNING:
+                        Transfer '{}' ({}) to {}?
+                        You may lose access to the project and its resources."#,
+                    proj.name,
+                    pid,
+                    new_owner,
+                )
+                .bold()
+                .red()
+            );
+            if !Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Are you sure?")
+                .default(false)
+                .interact()
+                .unwrap()
+            {
+                return Ok(());
+            }
+        }
+
+        let project = client.update_project(pid, request).await?;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json(&project);
+        }
+
+        println!(
+            "Transferred project '{}' ({}) to {}",
+            project.name, project.id, new_owner
+        );
+
+        Ok(())
+    }
+
     async fn projects_list(&self, table_args: TableArgs) -> Result<()> {
         let client = self.client.as_ref().unwrap();
         let all_projects = client.get_projects_list().await?.projects;
+        if self.output_mode != OutputMode::Normal {
+            return self.print_json_list(&all_projects);
+        }
         // partition by team id and print separate tables
         let mut all_projects_map = BTreeMap::new();
         for proj in all_projects {
@@ -1859,6 +2757,232 @@ impl Shuttle {
     }
 }
 
+impl Shuttle {
+    /// Print a single value in the selected JSON output mode
+    fn print_json<T: Serialize>(&self, value: &T) -> Result<()> {
+        match self.output_mode {
+            OutputMode::Json => println!("{}", serde_json::to_string_pretty(value)?),
+            _ => println!("{}", serde_json::to_string(value)?),
+        }
+
+        Ok(())
+    }
+
+    /// Print a list as a JSON array, or as one JSON object per line in ndjson mode
+    fn print_json_list<T: Serialize>(&self, items: &[T]) -> Result<()> {
+        match self.output_mode {
+            OutputMode::Ndjson => {
+                for item in items {
+                    println!("{}", serde_json::to_string(item)?);
+                }
+            }
+            _ => println!("{}", serde_json::to_string_pretty(items)?),
+        }
+
+        Ok(())
+    }
+}
+
+fn print_secrets_redeploy_note() {
+    eprintln!(
+        "{}",
+        formatdoc! {"
+            Note:
+                Running deployments keep the secrets they were started with.
+                Use `shuttle deployment redeploy` to apply the changes without deploying new code."
+        }
+        .yellow(),
+    );
+}
+
+/// Ask for the configuration of a telemetry sink, hiding tokens as they are typed
+fn prompt_telemetry_sink_config(sink: TelemetrySink) -> Result<TelemetrySinkConfig> {
+    let theme = ColorfulTheme::default();
+    let secret = |prompt: &str| -> Result<Secret<String>> {
+        Ok(Secret::new(
+            Password::with_theme(&theme)
+                .with_prompt(prompt)
+                .interact()?,
+        ))
+    };
+
+    Ok(match sink {
+        TelemetrySink::Betterstack => TelemetrySinkConfig::Betterstack(BetterstackConfig {
+            ingesting_host: Input::<String>::with_theme(&theme)
+                .with_prompt("Ingesting host")
+                .default("in-otel.logs.betterstack.com".to_owned())
+                .interact_text()?
+                .into(),
+            source_token: secret("Source token")?,
+        }),
+        TelemetrySink::Datadog => TelemetrySinkConfig::Datadog(DatadogConfig {
+            api_key: secret("API key")?,
+        }),
+        TelemetrySink::GrafanaCloud => TelemetrySinkConfig::GrafanaCloud(GrafanaCloudConfig {
+            endpoint: Input::with_theme(&theme)
+                .with_prompt("OTLP endpoint")
+                .interact_text()?,
+            instance_id: Input::with_theme(&theme)
+                .with_prompt("Instance ID")
+                .interact_text()?,
+            token: secret("Access policy token")?,
+        }),
+        TelemetrySink::Otlp => {
+            let endpoint = Input::with_theme(&theme)
+                .with_prompt("OTLP/HTTP endpoint")
+                .interact_text()?;
+            let mut headers = BTreeMap::new();
+            loop {
+                let name: String = Input::with_theme(&theme)
+                    .with_prompt("Header to send, such as 'authorization' (leave empty to finish)")
+                    .allow_empty(true)
+                    .interact_text()?;
+                if name.is_empty() {
+                    break;
+                }
+                let value = secret(&format!("Value of {name}"))?;
+                headers.insert(name, value);
+            }
+
+            TelemetrySinkConfig::Otlp(OtlpConfig { endpoint, headers })
+        }
+    })
+}
+
+/// Mask passwords and secret values in a resource's output
+fn redact_resource_output(resource: &mut ResourceResponse) {
+    match resource.r#type {
+        ResourceType::Secrets => {
+            if let Some(secrets) = resource.output.as_object_mut() {
+                for value in secrets.values_mut() {
+                    *value = "********".into();
+                }
+            }
+        }
+        ResourceType::DatabaseSharedPostgres
+        | ResourceType::DatabaseAwsRdsMariaDB
+        | ResourceType::DatabaseAwsRdsMySql
+        | ResourceType::DatabaseAwsRdsPostgres
+        | ResourceType::DatabaseSharedRedis => {
+            if let Some(password) = resource.output.get_mut("role_password") {
+                *password = "********".into();
+            }
+        }
+        ResourceType::StorageBucket => {
+            for key in ["secret_access_key", "session_token"] {
+                if let Some(value) = resource.output.get_mut(key).filter(|v| !v.is_null()) {
+                    *value = "********".into();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Print a line of runtime output in a local run
+fn print_runtime_line(source: &str, line: String, raw: bool) {
+    if !raw {
+        if let Some(report) = RuntimeExitReport::from_line(&line) {
+            // stopping a local run is not worth explaining
+            if report.reason != RuntimeExitReason::Interrupted {
+                println!("{}", runtime_exit_diagnostic(&report));
+            }
+            return;
+        }
+    }
+    if raw {
+        println!("{}", line);
+    } else {
+        let log_item = LogItem::new(Utc::now(), source.to_owned(), line);
+        println!("{log_item}");
+    }
+}
+
+/// Explain why the runtime exited, with a hint on what to do about it
+fn runtime_exit_diagnostic(report: &RuntimeExitReport) -> String {
+    let hint = match report.reason {
+        RuntimeExitReason::SecretsFailed => Some(
+            "The runtime could not get the project's secrets. \
+            In a local run, check that your Secrets.toml is valid TOML.",
+        ),
+        RuntimeExitReason::LoaderFailed => Some(
+            "A resource attribute on your main function failed to build. \
+            Check its options and the secrets they use.",
+        ),
+        RuntimeExitReason::InvalidResourceInput => Some(
+            "A resource plugin sent an invalid request. \
+            Make sure your Shuttle crates are on the same version as shuttle-runtime.",
+        ),
+        RuntimeExitReason::ProvisioningFailed => Some(
+            "In a local run, make sure Docker is running. \
+            Otherwise, check the resource with `shuttle resource list`.",
+        ),
+        RuntimeExitReason::ProvisioningBadState => Some(
+            "Check the resource with `shuttle resource list`, \
+            and delete it with `shuttle resource delete` to have it recreated.",
+        ),
+        RuntimeExitReason::ProvisioningTimedOut => Some(
+            "Try again. In a local run, the timeout can be raised with \
+            SHUTTLE_PROVISION_TIMEOUT (seconds).",
+        ),
+        RuntimeExitReason::ResourceInitFailed => {
+            Some("Your main function, or turning a resource into its input, returned an error.")
+        }
+        RuntimeExitReason::BindFailed => Some(
+            "Your service returned an error while running. \
+            Check that its port is not already in use.",
+        ),
+        RuntimeExitReason::HealthCheckFailed
+        | RuntimeExitReason::HealthCheckBindFailed
+        | RuntimeExitReason::HealthCheckListenerFailed => Some(
+            "The runtime could not serve health checks. Check that the health check port is free.",
+        ),
+        RuntimeExitReason::Interrupted | RuntimeExitReason::Unknown => None,
+    };
+
+    let mut out = format!(
+        "\n{} in the {} phase ({}, exit code {})",
+        "Runtime exited".bold().red(),
+        report.phase.to_string().replace('_', " "),
+        report.reason,
+        report.code,
+    );
+    if let Some(resource_type) = report.resource_type {
+        out.push_str(&format!("\n  resource: {resource_type}"));
+    }
+    for (i, error) in report.errors.iter().enumerate() {
+        let label = if i == 0 { "error" } else { "caused by" };
+        out.push_str(&format!("\n  {label}: {error}"));
+    }
+    if let Some(hint) = hint {
+        out.push_str(&format!("\n  {} {hint}", "hint:".yellow()));
+    }
+
+    out
+}
+
+/// Wait until one of the runtime processes exits, returning its name and exit status
+async fn wait_for_any_runtime(
+    runtimes: &mut [(String, tokio::process::Child)],
+) -> (String, std::io::Result<std::process::ExitStatus>) {
+    let (exit_result, index, _) = futures::future::select_all(
+        runtimes
+            .iter_mut()
+            .map(|(_, runtime)| Box::pin(runtime.wait())),
+    )
+    .await;
+
+    (runtimes[index].0.clone(), exit_result)
+}
+
+fn print_log(log: &LogItem, raw: bool) {
+    if raw {
+        println!("{}", log.line);
+    } else {
+        println!("{log}");
+    }
+}
+
 /// Calls async function `f` in a loop with `millis` sleep between iterations,
 /// providing iteration count and reference to update the progress bar.
 /// `f` returns Some with a cleanup function if done.
@@ -1997,6 +3121,7 @@ mod tests {
             DeployArgs {
                 secret_args: SecretsArgs {
                     secrets: Some(working_directory.join("Secrets.toml.example")),
+                    ..Default::default()
                 },
                 ..Default::default()
             },
@@ -2038,4 +3163,30 @@ mod tests {
             path_from_workspace_root("examples/axum/hello-world")
         );
     }
+
+    #[test]
+    fn redacts_resource_output() {
+        use shuttle_common::models::resource::{ResourceResponse, ResourceState, ResourceType};
+
+        let mut secrets = ResourceResponse {
+            r#type: ResourceType::Secrets,
+            state: ResourceState::Ready,
+            config: serde_json::json!({}),
+            output: serde_json::json!({ "API_KEY": "hunter2" }),
+        };
+        super::redact_resource_output(&mut secrets);
+        assert_eq!(secrets.output, serde_json::json!({ "API_KEY": "********" }));
+
+        let mut database = ResourceResponse {
+            r#type: ResourceType::DatabaseSharedPostgres,
+            state: ResourceState::Ready,
+            config: serde_json::json!({}),
+            output: serde_json::json!({ "role_name": "user", "role_password": "hunter2" }),
+        };
+        super::redact_resource_output(&mut database);
+        assert_eq!(
+            database.output,
+            serde_json::json!({ "role_name": "user", "role_password": "********" })
+        );
+    }
 }
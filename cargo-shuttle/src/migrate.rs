@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use comfy_table::{
+    presets::{NOTHING, UTF8_BORDERS_ONLY},
+    ContentArrangement, Table,
+};
+use serde::Serialize;
+use sqlx::{
+    any::AnyPoolOptions,
+    migrate::{Migrate, Migrator},
+};
+use tracing::trace;
+
+/// A migration in the migrations directory, and whether it has been applied to the database
+#[derive(Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, strum::Display)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    /// Was applied before this run
+    #[strum(to_string = "applied")]
+    Applied,
+    /// Was applied by this run
+    #[strum(to_string = "newly applied")]
+    NewlyApplied,
+    /// Not applied, since this was a dry run
+    #[strum(to_string = "pending")]
+    Pending,
+}
+
+/// Whether `dir` holds diesel migrations (a `<version>_<name>/up.sql` per migration)
+/// rather than sqlx migrations (`<version>_<name>.sql` files)
+fn is_diesel_layout(dir: &Path) -> Result<bool> {
+    Ok(std::fs::read_dir(dir)
+        .with_context(|| format!("reading migrations directory {}", dir.display()))?
+        .filter_map(Result::ok)
+        .any(|entry| entry.path().join("up.sql").is_file()))
+}
+
+/// Run the pending sqlx migrations in `dir` against a Postgres or MySQL database,
+/// and return the state of every migration.
+/// Nothing is run on a `dry_run`, but the pending migrations are still listed.
+pub async fn run_migrations(
+    connection_string: &str,
+    dir: &Path,
+    dry_run: bool,
+) -> Result<Vec<MigrationStatus>> {
+    if is_diesel_layout(dir)? {
+        bail!(
+            "{} holds diesel migrations, which can't be run by this command. \
+            Run them with `diesel migration run --migration-dir {} --database-url <url>`, \
+            using the connection string from `shuttle resource list --show-secrets`.",
+            dir.display(),
+            dir.display(),
+        );
+    }
+
+    sqlx::any::install_default_drivers();
+    let migrator = Migrator::new(dir)
+        .await
+        .with_context(|| format!("loading migrations from {}", dir.display()))?;
+
+    // a pool keeps the future Send, which running on a `&mut AnyConnection` does not
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .connect(connection_string)
+        .await
+        .context("connecting to the database")?;
+    let mut conn = pool.acquire().await.context("connecting to the database")?;
+    conn.ensure_migrations_table()
+        .await
+        .context("creating the migrations table")?;
+    let applied = conn
+        .list_applied_migrations()
+        .await
+        .context("listing applied migrations")?
+        .into_iter()
+        .map(|m| m.version)
+        .collect::<HashSet<_>>();
+    trace!(?applied, "applied migrations");
+    drop(conn);
+
+    if !dry_run {
+        migrator.run(&pool).await.context("running migrations")?;
+    }
+    pool.close().await;
+
+    Ok(migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| MigrationStatus {
+            version: m.version,
+            description: m.description.to_string(),
+            state: if applied.contains(&m.version) {
+                MigrationState::Applied
+            } else if dry_run {
+                MigrationState::Pending
+            } else {
+                MigrationState::NewlyApplied
+            },
+        })
+        .collect())
+}
+
+pub fn get_migrations_table(migrations: &[MigrationStatus], raw: bool) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(if raw { NOTHING } else { UTF8_BORDERS_ONLY })
+        .set_content_arrangement(ContentArrangement::Disabled)
+        .set_header(vec!["Version", "Description", "State"]);
+
+    for migration in migrations {
+        table.add_row(vec![
+            migration.version.to_string(),
+            migration.description.clone(),
+            migration.state.to_string(),
+        ]);
+    }
+
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_migration_layout() {
+        let sqlx = tempfile::tempdir().unwrap();
+        std::fs::write(sqlx.path().join("20240101000000_init.sql"), "").unwrap();
+        assert!(!is_diesel_layout(sqlx.path()).unwrap());
+
+        let diesel = tempfile::tempdir().unwrap();
+        let migration = diesel.path().join("2024-01-01-000000_init");
+        std::fs::create_dir(&migration).unwrap();
+        std::fs::write(migration.join("up.sql"), "").unwrap();
+        std::fs::write(migration.join("down.sql"), "").unwrap();
+        assert!(is_diesel_layout(diesel.path()).unwrap());
+
+        assert!(is_diesel_layout(&diesel.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,592 @@
+//! A local OpenTelemetry collector for `cargo shuttle run --otel`.
+//!
+//! Receives OTLP over gRPC and HTTP (protobuf or JSON), and either pretty-prints the
+//! spans, logs, and metrics or appends them to a file as OTLP JSON lines.
+
+use std::{
+    convert::Infallible,
+    fs::File,
+    io::Write,
+    net::SocketAddr,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, SecondsFormat};
+use crossterm::style::{StyledContent, Stylize};
+use http_body_util::{BodyExt, Full};
+use hyper::{
+    body::{self, Bytes},
+    header::CONTENT_TYPE,
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use opentelemetry_proto::tonic::{
+    collector::{
+        logs::v1::{
+            logs_service_server::{LogsService, LogsServiceServer},
+            ExportLogsServiceRequest, ExportLogsServiceResponse,
+        },
+        metrics::v1::{
+            metrics_service_server::{MetricsService, MetricsServiceServer},
+            ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+        },
+        trace::v1::{
+            trace_service_server::{TraceService, TraceServiceServer},
+            ExportTraceServiceRequest, ExportTraceServiceResponse,
+        },
+    },
+    common::v1::{any_value::Value, AnyValue, KeyValue},
+    metrics::v1::{metric::Data, number_data_point, NumberDataPoint},
+    resource::v1::Resource,
+    trace::v1::status::StatusCode as SpanStatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::net::TcpListener;
+use tonic::transport::{server::TcpIncoming, Server};
+use tracing::{debug, error};
+
+/// Where received telemetry goes
+enum Output {
+    Print,
+    /// OTLP JSON, one export request per line
+    File(Mutex<File>),
+}
+
+#[derive(Clone)]
+pub struct OtelCollector {
+    output: Arc<Output>,
+}
+
+impl OtelCollector {
+    /// Print received telemetry, or append it to `file` if given
+    pub fn new(file: Option<&Path>) -> Result<Self> {
+        let output = match file {
+            Some(path) => Output::File(Mutex::new(
+                File::options()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("opening {}", path.display()))?,
+            )),
+            None => Output::Print,
+        };
+
+        Ok(Self {
+            output: Arc::new(output),
+        })
+    }
+
+    /// Bind the receivers and serve them in the background
+    pub async fn start(self, grpc_addr: SocketAddr, http_addr: SocketAddr) -> Result<()> {
+        let grpc_listener = TcpListener::bind(grpc_addr)
+            .await
+            .with_context(|| format!("binding the OTLP/gRPC receiver to {grpc_addr}"))?;
+        let http_listener = TcpListener::bind(http_addr)
+            .await
+            .with_context(|| format!("binding the OTLP/HTTP receiver to {http_addr}"))?;
+
+        let incoming = TcpIncoming::from_listener(grpc_listener, true, None)
+            .map_err(|e| anyhow::anyhow!(e))
+            .context("accepting OTLP/gRPC connections")?;
+        let grpc = Server::builder()
+            .add_service(TraceServiceServer::new(self.clone()))
+            .add_service(LogsServiceServer::new(self.clone()))
+            .add_service(MetricsServiceServer::new(self.clone()))
+            .serve_with_incoming(incoming);
+        tokio::spawn(async move {
+            if let Err(e) = grpc.await {
+                error!(error = %e, "OTLP/gRPC receiver failed");
+            }
+        });
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = match http_listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        error!(error = %e, "OTLP/HTTP receiver failed to accept a connection");
+                        // avoid spinning when the error persists, such as when out of file descriptors
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+                let collector = self.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = http1::Builder::new()
+                        .serve_connection(
+                            TokioIo::new(stream),
+                            service_fn(|req| collector.clone().handle_http(req)),
+                        )
+                        .await
+                    {
+                        debug!(error = %e, "OTLP/HTTP connection closed");
+                    }
+                });
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn handle_http(
+        self,
+        req: Request<body::Incoming>,
+    ) -> std::result::Result<Response<Full<Bytes>>, Infallible> {
+        let method = req.method().clone();
+        let path = req.uri().path().to_owned();
+        let json = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("application/json"));
+        let body = match req.into_body().collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(e) => return Ok(http_error(StatusCode::BAD_REQUEST, e)),
+        };
+
+        let response = match (method, path.as_str()) {
+            (Method::POST, "/v1/traces") => decode(&body, json).map(|req| {
+                self.traces(&req);
+                encode(&ExportTraceServiceResponse::default(), json)
+            }),
+            (Method::POST, "/v1/logs") => decode(&body, json).map(|req| {
+                self.logs(&req);
+                encode(&ExportLogsServiceResponse::default(), json)
+            }),
+            (Method::POST, "/v1/metrics") => decode(&body, json).map(|req| {
+                self.metrics(&req);
+                encode(&ExportMetricsServiceResponse::default(), json)
+            }),
+            _ => {
+                return Ok(http_error(
+                    StatusCode::NOT_FOUND,
+                    format!("no OTLP endpoint at {path}"),
+                ))
+            }
+        };
+
+        Ok(match response {
+            Ok(bytes) => Response::builder()
+                .header(
+                    CONTENT_TYPE,
+                    if json {
+                        "application/json"
+                    } else {
+                        "application/x-protobuf"
+                    },
+                )
+                .body(Full::new(Bytes::from(bytes)))
+                .unwrap(),
+            Err(e) => http_error(StatusCode::BAD_REQUEST, e),
+        })
+    }
+
+    fn traces(&self, req: &ExportTraceServiceRequest) {
+        match self.output.as_ref() {
+            Output::Print => print_traces(req),
+            Output::File(file) => write_line(file, req),
+        }
+    }
+
+    fn logs(&self, req: &ExportLogsServiceRequest) {
+        match self.output.as_ref() {
+            Output::Print => print_logs(req),
+            Output::File(file) => write_line(file, req),
+        }
+    }
+
+    fn metrics(&self, req: &ExportMetricsServiceRequest) {
+        match self.output.as_ref() {
+            Output::Print => print_metrics(req),
+            Output::File(file) => write_line(file, req),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl TraceService for OtelCollector {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportTraceServiceRequest>,
+    ) -> std::result::Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
+        self.traces(request.get_ref());
+        Ok(tonic::Response::new(ExportTraceServiceResponse::default()))
+    }
+}
+
+#[tonic::async_trait]
+impl LogsService for OtelCollector {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportLogsServiceRequest>,
+    ) -> std::result::Result<tonic::Response<ExportLogsServiceResponse>, tonic::Status> {
+        self.logs(request.get_ref());
+        Ok(tonic::Response::new(ExportLogsServiceResponse::default()))
+    }
+}
+
+#[tonic::async_trait]
+impl MetricsService for OtelCollector {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportMetricsServiceRequest>,
+    ) -> std::result::Result<tonic::Response<ExportMetricsServiceResponse>, tonic::Status> {
+        self.metrics(request.get_ref());
+        Ok(tonic::Response::new(ExportMetricsServiceResponse::default()))
+    }
+}
+
+fn decode<T: prost::Message + Default + DeserializeOwned>(body: &[u8], json: bool) -> Result<T> {
+    Ok(if json {
+        serde_json::from_slice(body)?
+    } else {
+        T::decode(body)?
+    })
+}
+
+fn encode<T: prost::Message + Serialize>(msg: &T, json: bool) -> Vec<u8> {
+    if json {
+        serde_json::to_vec(msg).unwrap()
+    } else {
+        msg.encode_to_vec()
+    }
+}
+
+fn http_error(status: StatusCode, e: impl std::fmt::Display) -> Response<Full<Bytes>> {
+    debug!(%status, error = %e, "rejecting OTLP/HTTP request");
+    Response::builder()
+        .status(status)
+        .body(Full::new(Bytes::from(e.to_string())))
+        .unwrap()
+}
+
+fn write_line(file: &Mutex<File>, req: &impl Serialize) {
+    let mut line = serde_json::to_vec(req).unwrap();
+    line.push(b'\n');
+    if let Err(e) = file.lock().unwrap().write_all(&line) {
+        error!(error = %e, "failed to write telemetry to file");
+    }
+}
+
+fn print_traces(req: &ExportTraceServiceRequest) {
+    for resource_spans in &req.resource_spans {
+        let service = service_name(resource_spans.resource.as_ref());
+        for span in resource_spans
+            .scope_spans
+            .iter()
+            .flat_map(|s| s.spans.iter())
+        {
+            let duration = span
+                .end_time_unix_nano
+                .saturating_sub(span.start_time_unix_nano) as f64
+                / 1_000_000.0;
+            let mut line = format!(
+                "{} {} {:.2}ms trace_id={} span_id={}",
+                header(span.start_time_unix_nano, "span".magenta(), service),
+                span.name.as_str().bold(),
+                duration,
+                hex(&span.trace_id),
+                hex(&span.span_id),
+            );
+            if !span.parent_span_id.is_empty() {
+                line.push_str(&format!(" parent_span_id={}", hex(&span.parent_span_id)));
+            }
+            line.push_str(&attributes(&span.attributes));
+            if let Some(status) = span
+                .status
+                .as_ref()
+                .filter(|s| s.code == SpanStatusCode::Error as i32)
+            {
+                line.push_str(&format!(" {}", format!("ERROR {}", status.message).red()));
+            }
+            println!("{line}");
+
+            for event in &span.events {
+                println!(
+                    "    {} {}{}",
+                    "event".dark_grey(),
+                    event.name,
+                    attributes(&event.attributes)
+                );
+            }
+        }
+    }
+}
+
+fn print_logs(req: &ExportLogsServiceRequest) {
+    for resource_logs in &req.resource_logs {
+        let service = service_name(resource_logs.resource.as_ref());
+        for record in resource_logs
+            .scope_logs
+            .iter()
+            .flat_map(|s| s.log_records.iter())
+        {
+            let time = if record.time_unix_nano != 0 {
+                record.time_unix_nano
+            } else {
+                record.observed_time_unix_nano
+            };
+            let body = record.body.as_ref().map(format_value).unwrap_or_default();
+            println!(
+                "{} {} {}{}",
+                header(time, "log".cyan(), service),
+                severity(&record.severity_text, record.severity_number),
+                body,
+                attributes(&record.attributes),
+            );
+        }
+    }
+}
+
+fn print_metrics(req: &ExportMetricsServiceRequest) {
+    for resource_metrics in &req.resource_metrics {
+        let service = service_name(resource_metrics.resource.as_ref());
+        for metric in resource_metrics
+            .scope_metrics
+            .iter()
+            .flat_map(|s| s.metrics.iter())
+        {
+            // "1" is the unit of dimensionless values such as counts
+            let unit = if metric.unit.is_empty() || metric.unit == "1" {
+                String::new()
+            } else {
+                format!(" {}", metric.unit)
+            };
+            // (time, value, attributes) of each data point
+            let points: Vec<(u64, String, &[KeyValue])> = match &metric.data {
+                Some(Data::Gauge(gauge)) => number_points(&gauge.data_points, &unit),
+                Some(Data::Sum(sum)) => number_points(&sum.data_points, &unit),
+                Some(Data::Histogram(histogram)) => histogram
+                    .data_points
+                    .iter()
+                    .map(|p| {
+                        (
+                            p.time_unix_nano,
+                            format!("count={} sum={}{unit}", p.count, p.sum.unwrap_or_default()),
+                            p.attributes.as_slice(),
+                        )
+                    })
+                    .collect(),
+                Some(Data::ExponentialHistogram(histogram)) => histogram
+                    .data_points
+                    .iter()
+                    .map(|p| {
+                        (
+                            p.time_unix_nano,
+                            format!("count={} sum={}{unit}", p.count, p.sum.unwrap_or_default()),
+                            p.attributes.as_slice(),
+                        )
+                    })
+                    .collect(),
+                Some(Data::Summary(summary)) => summary
+                    .data_points
+                    .iter()
+                    .map(|p| {
+                        (
+                            p.time_unix_nano,
+                            format!("count={} sum={}{unit}", p.count, p.sum),
+                            p.attributes.as_slice(),
+                        )
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+            for (time, value, attrs) in points {
+                println!(
+                    "{} {} = {}{}",
+                    header(time, "metric".yellow(), service),
+                    metric.name.as_str().bold(),
+                    value,
+                    attributes(attrs),
+                );
+            }
+        }
+    }
+}
+
+fn number_points<'a>(
+    points: &'a [NumberDataPoint],
+    unit: &str,
+) -> Vec<(u64, String, &'a [KeyValue])> {
+    points
+        .iter()
+        .map(|p| {
+            let value = match p.value {
+                Some(number_data_point::Value::AsDouble(v)) => v.to_string(),
+                Some(number_data_point::Value::AsInt(v)) => v.to_string(),
+                None => String::new(),
+            };
+            (
+                p.time_unix_nano,
+                format!("{value}{unit}"),
+                p.attributes.as_slice(),
+            )
+        })
+        .collect()
+}
+
+/// Timestamp, signal type, and service name at the start of every line
+fn header(time_unix_nano: u64, signal: StyledContent<&str>, service: &str) -> String {
+    let time = DateTime::from_timestamp_nanos(time_unix_nano as i64)
+        .with_timezone(&Local)
+        .to_rfc3339_opts(SecondsFormat::Millis, false);
+    format!("{} [{signal}] [{}]", time.dim(), service.cyan())
+}
+
+fn severity(text: &str, number: i32) -> String {
+    // https://opentelemetry.io/docs/specs/otel/logs/data-model/#field-severitynumber
+    let text = if text.is_empty() {
+        match number {
+            1..=4 => "TRACE",
+            5..=8 => "DEBUG",
+            9..=12 => "INFO",
+            13..=16 => "WARN",
+            17..=24 => "ERROR",
+            _ => "UNSPECIFIED",
+        }
+        .to_owned()
+    } else {
+        text.to_uppercase()
+    };
+    match number {
+        17..=24 => text.red().to_string(),
+        13..=16 => text.yellow().to_string(),
+        9..=12 => text.green().to_string(),
+        _ => text.dark_grey().to_string(),
+    }
+}
+
+fn service_name(resource: Option<&Resource>) -> &str {
+    resource
+        .and_then(|r| r.attributes.iter().find(|kv| kv.key == "service.name"))
+        .and_then(|kv| kv.value.as_ref())
+        .and_then(|v| match &v.value {
+            Some(Value::StringValue(s)) => Some(s.as_str()),
+            _ => None,
+        })
+        .unwrap_or("unknown")
+}
+
+fn attributes(attrs: &[KeyValue]) -> String {
+    attrs
+        .iter()
+        .map(|kv| {
+            format!(
+                " {}={}",
+                kv.key.as_str().dark_grey(),
+                kv.value.as_ref().map(format_value).unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+fn format_value(value: &AnyValue) -> String {
+    match &value.value {
+        Some(Value::StringValue(s)) => s.clone(),
+        Some(Value::BoolValue(b)) => b.to_string(),
+        Some(Value::IntValue(i)) => i.to_string(),
+        Some(Value::DoubleValue(d)) => d.to_string(),
+        Some(Value::BytesValue(b)) => hex(b),
+        Some(Value::ArrayValue(a)) => format!(
+            "[{}]",
+            a.values
+                .iter()
+                .map(format_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(Value::KvlistValue(kvs)) => format!(
+            "{{{}}}",
+            kvs.values
+                .iter()
+                .map(|kv| format!(
+                    "{}: {}",
+                    kv.key,
+                    kv.value.as_ref().map(format_value).unwrap_or_default()
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None => String::new(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use opentelemetry_proto::tonic::{
+        resource::v1::Resource,
+        trace::v1::{ResourceSpans, ScopeSpans, Span},
+    };
+    use prost::Message;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn writes_received_spans_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("otel.jsonl");
+        let grpc_port = portpicker::pick_unused_port().unwrap();
+        let http_port = portpicker::pick_unused_port().unwrap();
+        OtelCollector::new(Some(&path))
+            .unwrap()
+            .start(
+                (Ipv4Addr::LOCALHOST, grpc_port).into(),
+                (Ipv4Addr::LOCALHOST, http_port).into(),
+            )
+            .await
+            .unwrap();
+
+        let req = ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                resource: Some(Resource {
+                    attributes: vec![KeyValue {
+                        key: "service.name".into(),
+                        value: Some(AnyValue {
+                            value: Some(Value::StringValue("my-service".into())),
+                        }),
+                    }],
+                    dropped_attributes_count: 0,
+                }),
+                scope_spans: vec![ScopeSpans {
+                    spans: vec![Span {
+                        trace_id: vec![1; 16],
+                        span_id: vec![2; 8],
+                        name: "GET /hello".into(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let res = reqwest::Client::new()
+            .post(format!("http://127.0.0.1:{http_port}/v1/traces"))
+            .header(CONTENT_TYPE, "application/x-protobuf")
+            .body(req.encode_to_vec())
+            .send()
+            .await
+            .unwrap();
+        assert!(res.status().is_success());
+        ExportTraceServiceResponse::decode(res.bytes().await.unwrap()).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let line: ExportTraceServiceRequest =
+            serde_json::from_str(written.lines().next().unwrap()).unwrap();
+        assert_eq!(line, req);
+        assert_eq!(
+            service_name(line.resource_spans[0].resource.as_ref()),
+            "my-service"
+        );
+    }
+}
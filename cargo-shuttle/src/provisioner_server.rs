@@ -1,6 +1,11 @@
 use std::{
-    collections::HashMap, convert::Infallible, io::stderr, net::SocketAddr, process::exit,
-    sync::Arc, time::Duration,
+    collections::HashMap,
+    convert::Infallible,
+    io::stderr,
+    net::SocketAddr,
+    process::exit,
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
 };
 
 use anyhow::{bail, Context, Result};
@@ -33,11 +38,26 @@ use shuttle_common::{
     },
     secrets::Secret,
     tables::get_resource_tables,
-    ContainerRequest, ContainerResponse, DatabaseInfo, DbInput,
+    BucketInput, ContainerRequest, ContainerResponse, DatabaseInfo, DbInput, S3Config,
 };
 use tokio::{net::TcpListener, time::sleep};
 use tracing::{debug, error, trace};
 
+/// Resources are requested concurrently by the runtime, so containers are created under a lock per container name
+fn container_lock(container_name: &str) -> Arc<tokio::sync::Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>> = OnceLock::new();
+    LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .expect("container locks to not be poisoned")
+        .entry(container_name.to_owned())
+        .or_default()
+        .clone()
+}
+
+/// Image pulls draw their progress over the same terminal lines, so only one runs at a time
+static PULL_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
 /// A provisioner for local runs
 /// It uses Docker to create Databases
 pub struct LocalProvisioner {
@@ -104,6 +124,7 @@ impl LocalProvisioner {
         image: &str,
         port: &str,
         env: Option<Vec<String>>,
+        cmd: Option<Vec<String>>,
     ) -> Result<ContainerInspectResponse> {
         match self.docker.inspect_container(container_name, None).await {
             Ok(container) => {
@@ -136,6 +157,7 @@ impl LocalProvisioner {
                 let config: Config<String> = Config {
                     image: Some(image.to_string()),
                     env,
+                    cmd,
                     host_config: Some(host_config),
                     ..Default::default()
                 };
@@ -175,6 +197,8 @@ impl LocalProvisioner {
             | ResourceType::DatabaseAwsRdsMariaDB => {
                 db_name.unwrap_or_else(|| project_name.to_string())
             }
+            // the database index
+            ResourceType::DatabaseSharedRedis => "0".to_string(),
             _ => project_name.to_string(),
         };
 
@@ -186,18 +210,22 @@ impl LocalProvisioner {
             password,
             port,
             env,
+            cmd,
             is_ready_cmd,
         } = db_type_to_config(db_type, &database_name);
         let container_name = format!("shuttle_{project_name}_{type}");
 
+        let lock = container_lock(&container_name);
+        let guard = lock.lock().await;
         let container = self
-            .get_container(&container_name, &image, &port, env)
+            .get_container(&container_name, &image, &port, env, cmd)
             .await?;
 
         let host_port = self.get_container_first_host_port(&container, &port);
 
         self.start_container_if_not_running(&container, &r#type, &container_name)
             .await;
+        drop(guard);
 
         self.wait_for_ready(&container_name, is_ready_cmd.clone())
             .await?;
@@ -227,12 +255,15 @@ impl LocalProvisioner {
             env,
             image,
             port,
+            cmd,
         } = req;
 
         let container_name = format!("shuttle_{project_name}_{container_name}");
 
+        let lock = container_lock(&container_name);
+        let _guard = lock.lock().await;
         let container = self
-            .get_container(&container_name, &image, &port, Some(env))
+            .get_container(&container_name, &image, &port, Some(env), cmd)
             .await?;
 
         let host_port = self.get_container_first_host_port(&container, &port);
@@ -243,6 +274,60 @@ impl LocalProvisioner {
         Ok(ContainerResponse { host_port })
     }
 
+    /// Start a MinIO container for the project and make sure that the bucket exists in it
+    pub async fn get_bucket(
+        &self,
+        project_name: &str,
+        bucket_name: Option<String>,
+    ) -> Result<S3Config> {
+        let bucket = bucket_name.unwrap_or_else(|| project_name.to_string());
+        // the name ends up in a shell command below
+        if !is_valid_bucket_name(&bucket) {
+            bail!(
+                "Invalid bucket name '{bucket}': use 3 to 63 lowercase letters, digits, dots and hyphens, \
+                starting and ending with a letter or digit"
+            );
+        }
+        let (user, password) = ("minio", "minio123");
+
+        let ContainerResponse { host_port } = self
+            .start_container(ContainerRequest {
+                project_name: project_name.to_string(),
+                container_name: "minio".to_string(),
+                image: "docker.io/minio/minio:latest".to_string(),
+                port: "9000/tcp".to_string(),
+                env: vec![
+                    format!("MINIO_ROOT_USER={user}"),
+                    format!("MINIO_ROOT_PASSWORD={password}"),
+                ],
+                cmd: Some(vec!["server".to_string(), "/data".to_string()]),
+            })
+            .await?;
+
+        // `mc` fails until the server is up, and only then prints to stdout
+        self.wait_for_ready(
+            &format!("shuttle_{project_name}_minio"),
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!(
+                    "mc alias set local http://127.0.0.1:9000 {user} {password} > /dev/null \
+                    && mc mb --ignore-existing local/{bucket} > /dev/null && echo ready"
+                ),
+            ],
+        )
+        .await?;
+
+        Ok(S3Config {
+            bucket,
+            region: "us-east-1".to_string(),
+            endpoint: Some(format!("http://localhost:{host_port}")),
+            access_key_id: user.to_string(),
+            secret_access_key: password.to_string(),
+            session_token: None,
+        })
+    }
+
     async fn wait_for_ready(&self, container_name: &str, is_ready_cmd: Vec<String>) -> Result<()> {
         loop {
             trace!("waiting for '{container_name}' to be ready for connections");
@@ -283,6 +368,7 @@ impl LocalProvisioner {
     }
 
     async fn pull_image(&self, image: &str) -> Result<(), String> {
+        let _guard = PULL_LOCK.lock().await;
         trace!("pulling latest image for '{image}'");
         let mut layers = Vec::new();
 
@@ -321,6 +407,19 @@ impl LocalProvisioner {
     }
 }
 
+/// S3 bucket naming rules: 3 to 63 lowercase letters, digits, dots and hyphens,
+/// starting and ending with a letter or digit
+fn is_valid_bucket_name(name: &str) -> bool {
+    let alphanumeric = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+
+    (3..=63).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| alphanumeric(c) || c == '.' || c == '-')
+        && name.starts_with(alphanumeric)
+        && name.ends_with(alphanumeric)
+}
+
 fn print_layers(layers: &Vec<CreateImageInfo>) {
     for info in layers {
         stderr()
@@ -367,6 +466,8 @@ struct EngineConfig {
     password: Secret<String>,
     port: String,
     env: Option<Vec<String>>,
+    /// Overrides the command of the image
+    cmd: Option<Vec<String>>,
     is_ready_cmd: Vec<String>,
 }
 
@@ -383,6 +484,7 @@ fn db_type_to_config(db_type: ResourceType, database_name: &str) -> EngineConfig
                 "POSTGRES_PASSWORD=postgres".to_string(),
                 format!("POSTGRES_DB={database_name}"),
             ]),
+            cmd: None,
             is_ready_cmd: vec![
                 "/bin/sh".to_string(),
                 "-c".to_string(),
@@ -400,6 +502,7 @@ fn db_type_to_config(db_type: ResourceType, database_name: &str) -> EngineConfig
                 "POSTGRES_PASSWORD=postgres".to_string(),
                 format!("POSTGRES_DB={database_name}"),
             ]),
+            cmd: None,
             is_ready_cmd: vec![
                 "/bin/sh".to_string(),
                 "-c".to_string(),
@@ -417,6 +520,7 @@ fn db_type_to_config(db_type: ResourceType, database_name: &str) -> EngineConfig
                 "MARIADB_ROOT_PASSWORD=mariadb".to_string(),
                 format!("MARIADB_DATABASE={database_name}"),
             ]),
+            cmd: None,
             is_ready_cmd: vec![
                 "mysql".to_string(),
                 "-pmariadb".to_string(),
@@ -436,6 +540,7 @@ fn db_type_to_config(db_type: ResourceType, database_name: &str) -> EngineConfig
                 "MYSQL_ROOT_PASSWORD=mysql".to_string(),
                 format!("MYSQL_DATABASE={database_name}"),
             ]),
+            cmd: None,
             is_ready_cmd: vec![
                 "mysql".to_string(),
                 "-pmysql".to_string(),
@@ -444,6 +549,27 @@ fn db_type_to_config(db_type: ResourceType, database_name: &str) -> EngineConfig
                 "show databases;".to_string(),
             ],
         },
+        ResourceType::DatabaseSharedRedis => EngineConfig {
+            r#type: "shared_redis".to_string(),
+            image: "docker.io/library/redis:7".to_string(),
+            engine: "redis".to_string(),
+            username: "default".to_string(),
+            password: "redis".to_string().into(),
+            port: "6379/tcp".to_string(),
+            env: None,
+            cmd: Some(vec![
+                "redis-server".to_string(),
+                "--requirepass".to_string(),
+                "redis".to_string(),
+            ]),
+            is_ready_cmd: vec![
+                "redis-cli".to_string(),
+                "-a".to_string(),
+                "redis".to_string(),
+                "--no-auth-warning".to_string(),
+                "ping".to_string(),
+            ],
+        },
         _ => panic!("Non-database resource type provided: {db_type}"),
     }
 }
@@ -528,7 +654,8 @@ async fn provision(
                 ResourceType::DatabaseSharedPostgres
                 | ResourceType::DatabaseAwsRdsMariaDB
                 | ResourceType::DatabaseAwsRdsMySql
-                | ResourceType::DatabaseAwsRdsPostgres => {
+                | ResourceType::DatabaseAwsRdsPostgres
+                | ResourceType::DatabaseSharedRedis => {
                     let config: DbInput = serde_json::from_value(shuttle_resource.config.clone())
                         .context("deserializing resource config")?;
                     let res = prov.get_db_connection_string(
@@ -558,6 +685,20 @@ async fn provision(
                         output: serde_json::to_value(res).unwrap(),
                     }
                 }
+                ResourceType::StorageBucket => {
+                    let config: BucketInput =
+                        serde_json::from_value(shuttle_resource.config.clone())
+                            .context("deserializing resource config")?;
+                    let res = prov.get_bucket(&state.project_name, config.bucket_name)
+                            .await
+                            .context("Failed to start object storage container. Make sure that a Docker engine is running.")?;
+                    ResourceResponse {
+                        r#type: shuttle_resource.r#type,
+                        state: resource::ResourceState::Ready,
+                        config: shuttle_resource.config,
+                        output: serde_json::to_value(res).unwrap(),
+                    }
+                }
                 ResourceType::Secrets => ResourceResponse {
                     r#type: shuttle_resource.r#type,
                     state: resource::ResourceState::Ready,
@@ -574,3 +715,27 @@ async fn provision(
         _ => bail!("Received unsupported resource request"),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_bucket_name;
+
+    #[test]
+    fn bucket_names() {
+        for name in ["my-project", "abc", "logs.2025", &"a".repeat(63)] {
+            assert!(is_valid_bucket_name(name), "{name}");
+        }
+        for name in [
+            "ab",
+            &"a".repeat(64),
+            "My-Project",
+            "-project",
+            "project.",
+            "my bucket",
+            "a;rm -rf /",
+            "$(id)",
+        ] {
+            assert!(!is_valid_bucket_name(name), "{name}");
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use shuttle_common::secrets::Secret;
+use tracing::trace;
+
+/// Environment variables (and `.env` entries) with this prefix are added as secrets, with the prefix removed
+pub const SECRETS_ENV_PREFIX: &str = "SHUTTLE_SECRET_";
+
+/// The secrets file for an environment, or the base secrets file if `env` is None
+fn secrets_file_name(env: Option<&str>) -> String {
+    match env {
+        Some(env) => format!("Secrets.{env}.toml"),
+        None => "Secrets.toml".to_owned(),
+    }
+}
+
+/// Whether the directory has a base or environment-specific secrets file
+pub fn has_secrets_file(dir: &Path, env: Option<&str>) -> bool {
+    dir.join(secrets_file_name(None)).is_file()
+        || env.is_some_and(|env| dir.join(secrets_file_name(Some(env))).is_file())
+}
+
+/// Resolve local secrets from these layers, where later layers override earlier ones:
+/// 1. `Secrets.toml` in `root`, or `file` if given
+/// 2. `Secrets.<env>.toml` in `root` (skipped if `file` is given)
+/// 3. [`SECRETS_ENV_PREFIX`] entries in a `.env` file in `root`
+/// 4. [`SECRETS_ENV_PREFIX`] entries in `vars`, usually the process environment
+///
+/// Returns None if no layer had any secrets.
+pub fn resolve_secrets(
+    root: &Path,
+    file: Option<&Path>,
+    env: Option<&str>,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<Option<BTreeMap<String, String>>> {
+    let mut secrets = BTreeMap::new();
+    let mut found = false;
+
+    let files = match file {
+        Some(file) => vec![file.to_path_buf()],
+        None => std::iter::once(None)
+            .chain(env.map(Some))
+            .map(|env| root.join(secrets_file_name(env)))
+            .collect(),
+    };
+    for file in files {
+        if !file.is_file() {
+            trace!("No secrets file at {}", file.display());
+            continue;
+        }
+        trace!("Loading secrets from {}", file.display());
+        let content = read_to_string(&file)
+            .with_context(|| format!("reading secrets file {}", file.display()))?;
+        let layer = parse_secrets(&content)
+            .with_context(|| format!("parsing secrets file {}", file.display()))?;
+        secrets.extend(layer);
+        found = true;
+    }
+
+    let dotenv = root.join(".env");
+    if dotenv.is_file() {
+        trace!("Loading secrets from {}", dotenv.display());
+        let content =
+            read_to_string(&dotenv).with_context(|| format!("reading {}", dotenv.display()))?;
+        let layer = prefixed_vars(parse_dotenv(&content));
+        found |= !layer.is_empty();
+        secrets.extend(layer);
+    }
+
+    let layer = prefixed_vars(vars);
+    found |= !layer.is_empty();
+    secrets.extend(layer);
+
+    trace!(keys = ?secrets.keys(), "available secrets");
+
+    Ok(found.then_some(secrets))
+}
+
+/// Parse the content of a secrets file into flat keys, see [`flatten_table`]
+pub fn parse_secrets(content: &str) -> Result<BTreeMap<String, String>> {
+    let table = toml::from_str::<toml::Table>(content)?;
+    let mut secrets = BTreeMap::new();
+    flatten_table(None, table, &mut secrets);
+
+    Ok(secrets)
+}
+
+/// Flatten nested TOML tables into dotted keys (`[database] url = ".."` becomes `database.url`),
+/// and arrays into indexed keys (`hosts = ["a", "b"]` becomes `hosts.0` and `hosts.1`).
+/// Non-string values are kept in their TOML representation.
+fn flatten_table(prefix: Option<&str>, table: toml::Table, out: &mut BTreeMap<String, String>) {
+    for (key, value) in table {
+        let key = match prefix {
+            Some(prefix) => format!("{prefix}.{key}"),
+            None => key,
+        };
+        flatten_value(key, value, out);
+    }
+}
+
+fn flatten_value(key: String, value: toml::Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => flatten_table(Some(&key), table, out),
+        toml::Value::Array(values) => {
+            for (i, value) in values.into_iter().enumerate() {
+                flatten_value(format!("{key}.{i}"), value, out);
+            }
+        }
+        toml::Value::String(value) => {
+            out.insert(key, value);
+        }
+        other => {
+            out.insert(key, other.to_string());
+        }
+    }
+}
+
+/// Keep the variables that start with [`SECRETS_ENV_PREFIX`], with the prefix removed
+fn prefixed_vars(vars: impl IntoIterator<Item = (String, String)>) -> BTreeMap<String, String> {
+    vars.into_iter()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(SECRETS_ENV_PREFIX)
+                .filter(|key| !key.is_empty())
+                .map(|key| (key.to_owned(), value))
+        })
+        .collect()
+}
+
+/// Parse `KEY=VALUE` lines of a `.env` file, ignoring comments and `export` prefixes
+fn parse_dotenv(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+                .unwrap_or(value);
+
+            Some((key.trim().to_owned(), value.to_owned()))
+        })
+        .collect()
+}
+
+/// How a secret differs between two sets of secrets
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", content = "key", rename_all = "lowercase")]
+pub enum SecretDiff {
+    /// Only in the local secrets
+    Added(String),
+    /// Only in the deployed secrets
+    Removed(String),
+    /// In both, with different values
+    Changed(String),
+}
+
+impl SecretDiff {
+    pub fn key(&self) -> &str {
+        match self {
+            SecretDiff::Added(key) | SecretDiff::Removed(key) | SecretDiff::Changed(key) => key,
+        }
+    }
+}
+
+/// Compare local and deployed secrets by key and value
+pub fn diff_secrets(
+    local: &BTreeMap<String, Secret<String>>,
+    deployed: &BTreeMap<String, Secret<String>>,
+) -> Vec<SecretDiff> {
+    let mut diff = Vec::new();
+    for (key, value) in local {
+        match deployed.get(key) {
+            None => diff.push(SecretDiff::Added(key.clone())),
+            Some(deployed_value) if deployed_value != value => {
+                diff.push(SecretDiff::Changed(key.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    for key in deployed.keys() {
+        if !local.contains_key(key) {
+            diff.push(SecretDiff::Removed(key.clone()));
+        }
+    }
+    diff.sort_by(|a, b| a.key().cmp(b.key()));
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dotenv() {
+        let content = r#"
+            # comment
+            SHUTTLE_SECRET_A=1
+            export SHUTTLE_SECRET_B="two words"
+            SHUTTLE_SECRET_C='3'
+            OTHER=4
+            invalid line
+        "#;
+        assert_eq!(
+            prefixed_vars(parse_dotenv(content)),
+            BTreeMap::from([
+                ("A".to_owned(), "1".to_owned()),
+                ("B".to_owned(), "two words".to_owned()),
+                ("C".to_owned(), "3".to_owned()),
+            ])
+        );
+    }
+
+    #[test]
+    fn env_vars_override_files() {
+        let root = std::env::temp_dir().join(format!("shuttle-secrets-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            root.join("Secrets.toml"),
+            "A = 'base'\nB = 'base'\nC = 'base'",
+        )
+        .unwrap();
+        std::fs::write(root.join("Secrets.dev.toml"), "B = 'dev'").unwrap();
+        std::fs::write(root.join(".env"), "SHUTTLE_SECRET_C=dotenv").unwrap();
+
+        let secrets = resolve_secrets(
+            &root,
+            None,
+            Some("dev"),
+            [("SHUTTLE_SECRET_D".to_owned(), "var".to_owned())],
+        )
+        .unwrap()
+        .unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(
+            secrets,
+            BTreeMap::from([
+                ("A".to_owned(), "base".to_owned()),
+                ("B".to_owned(), "dev".to_owned()),
+                ("C".to_owned(), "dotenv".to_owned()),
+                ("D".to_owned(), "var".to_owned()),
+            ])
+        );
+    }
+
+    #[test]
+    fn flattens_nested_tables() {
+        let table = toml::from_str::<toml::Table>(
+            "api_key = 'abc'\nport = 8080\nhosts = ['a', 'b,c']\nservers = [{ port = 1 }]\n[database]\nurl = 'postgres://'\n",
+        )
+        .unwrap();
+        let mut secrets = BTreeMap::new();
+        flatten_table(None, table, &mut secrets);
+
+        assert_eq!(
+            secrets,
+            BTreeMap::from([
+                ("api_key".to_owned(), "abc".to_owned()),
+                ("database.url".to_owned(), "postgres://".to_owned()),
+                ("hosts.0".to_owned(), "a".to_owned()),
+                ("hosts.1".to_owned(), "b,c".to_owned()),
+                ("port".to_owned(), "8080".to_owned()),
+                ("servers.0.port".to_owned(), "1".to_owned()),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_nested_secrets_file() {
+        let secrets = parse_secrets(
+            "token = 'abc'\nhosts = ['a', 'b']\n[database]\nurl = 'postgres://'\npool = 5\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            secrets,
+            BTreeMap::from([
+                ("database.pool".to_owned(), "5".to_owned()),
+                ("database.url".to_owned(), "postgres://".to_owned()),
+                ("hosts.0".to_owned(), "a".to_owned()),
+                ("hosts.1".to_owned(), "b".to_owned()),
+                ("token".to_owned(), "abc".to_owned()),
+            ])
+        );
+        assert!(parse_secrets("not toml").is_err());
+    }
+
+    #[test]
+    fn no_secrets_found() {
+        let root = std::env::temp_dir().join("shuttle-secrets-nonexistent");
+        assert_eq!(
+            resolve_secrets(
+                &root,
+                None,
+                Some("dev"),
+                [("PATH".to_owned(), "/bin".to_owned())]
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn diffs_secrets() {
+        let local = BTreeMap::from([
+            ("A".to_owned(), Secret::new("1".to_owned())),
+            ("B".to_owned(), Secret::new("2".to_owned())),
+            ("C".to_owned(), Secret::new("3".to_owned())),
+        ]);
+        let deployed = BTreeMap::from([
+            ("B".to_owned(), Secret::new("2".to_owned())),
+            ("C".to_owned(), Secret::new("changed".to_owned())),
+            ("D".to_owned(), Secret::new("4".to_owned())),
+        ]);
+
+        assert_eq!(
+            diff_secrets(&local, &deployed),
+            vec![
+                SecretDiff::Added("A".to_owned()),
+                SecretDiff::Changed("C".to_owned()),
+                SecretDiff::Removed("D".to_owned()),
+            ]
+        );
+    }
+}
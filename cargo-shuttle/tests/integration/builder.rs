@@ -1,15 +1,22 @@
 use std::path::{Path, PathBuf};
 
-use cargo_shuttle::builder::{build_workspace, BuiltService};
+use cargo_shuttle::builder::{build_workspace, BuiltService, ServiceSelection};
 
 #[tokio::test]
 #[should_panic(expected = "Build failed. Is the Shuttle runtime missing?")]
 async fn not_shuttle() {
     let (tx, _) = tokio::sync::mpsc::channel::<String>(256);
     let project_path = format!("{}/tests/resources/not-shuttle", env!("CARGO_MANIFEST_DIR"));
-    build_workspace(Path::new(&project_path), false, tx, false)
-        .await
-        .unwrap();
+    build_workspace(
+        Path::new(&project_path),
+        None,
+        tx,
+        false,
+        &Default::default(),
+        &Default::default(),
+    )
+    .await
+    .unwrap();
 }
 
 #[tokio::test]
@@ -19,7 +26,16 @@ async fn not_shuttle() {
 async fn not_bin() {
     let (tx, _) = tokio::sync::mpsc::channel::<String>(256);
     let project_path = format!("{}/tests/resources/not-bin", env!("CARGO_MANIFEST_DIR"));
-    match build_workspace(Path::new(&project_path), false, tx, false).await {
+    match build_workspace(
+        Path::new(&project_path),
+        None,
+        tx,
+        false,
+        &Default::default(),
+        &Default::default(),
+    )
+    .await
+    {
         Ok(_) => {}
         Err(e) => panic!("{}", e.to_string()),
     }
@@ -31,9 +47,16 @@ async fn is_bin() {
     let project_path = format!("{}/tests/resources/is-bin", env!("CARGO_MANIFEST_DIR"));
 
     assert_eq!(
-        build_workspace(Path::new(&project_path), false, tx, false)
-            .await
-            .unwrap(),
+        build_workspace(
+            Path::new(&project_path),
+            None,
+            tx,
+            false,
+            &Default::default(),
+            &Default::default()
+        )
+        .await
+        .unwrap(),
         vec![BuiltService {
             workspace_path: PathBuf::from(&project_path),
             manifest_path: PathBuf::from(&project_path).join("Cargo.toml"),
@@ -51,12 +74,19 @@ async fn not_found() {
         "{}/tests/resources/non-existing",
         env!("CARGO_MANIFEST_DIR")
     );
-    build_workspace(Path::new(&project_path), false, tx, false)
-        .await
-        .unwrap();
+    build_workspace(
+        Path::new(&project_path),
+        None,
+        tx,
+        false,
+        &Default::default(),
+        &Default::default(),
+    )
+    .await
+    .unwrap();
 }
 
-// Test that workspace projects are compiled correctly
+// Test that a workspace project is compiled correctly when selected
 #[tokio::test]
 async fn workspace() {
     let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(256);
@@ -66,11 +96,57 @@ async fn workspace() {
         }
     });
     let project_path = format!("{}/tests/resources/workspace", env!("CARGO_MANIFEST_DIR"));
+    let selection = ServiceSelection {
+        package: Some("alpha2".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        build_workspace(
+            Path::new(&project_path),
+            None,
+            tx,
+            false,
+            &selection,
+            &Default::default()
+        )
+        .await
+        .unwrap(),
+        vec![BuiltService {
+            workspace_path: PathBuf::from(&project_path),
+            manifest_path: PathBuf::from(&project_path).join("alpha2/Cargo.toml"),
+            package_name: "alpha2".to_string(),
+            executable_path: PathBuf::from(&project_path).join("target/debug/alpha2"),
+        }]
+    );
+}
+
+// Test that all workspace projects are compiled correctly
+#[tokio::test]
+async fn workspace_all() {
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(256);
+    tokio::spawn(async move {
+        while let Some(l) = rx.recv().await {
+            println!("{l}");
+        }
+    });
+    let project_path = format!("{}/tests/resources/workspace", env!("CARGO_MANIFEST_DIR"));
+    let selection = ServiceSelection {
+        all: true,
+        ..Default::default()
+    };
 
     assert_eq!(
-        build_workspace(Path::new(&project_path), false, tx, false)
-            .await
-            .unwrap(),
+        build_workspace(
+            Path::new(&project_path),
+            None,
+            tx,
+            false,
+            &selection,
+            &Default::default()
+        )
+        .await
+        .unwrap(),
         vec![
             BuiltService {
                 workspace_path: PathBuf::from(&project_path),
@@ -87,3 +163,50 @@ async fn workspace() {
         ]
     );
 }
+
+#[tokio::test]
+#[should_panic(
+    expected = "Found multiple Shuttle services in this workspace. Select one with `--package` or `build.package` in Shuttle.toml. Candidates: alpha, alpha2"
+)]
+async fn workspace_ambiguous() {
+    let (tx, _) = tokio::sync::mpsc::channel::<String>(256);
+    let project_path = format!("{}/tests/resources/workspace", env!("CARGO_MANIFEST_DIR"));
+    match build_workspace(
+        Path::new(&project_path),
+        None,
+        tx,
+        false,
+        &Default::default(),
+        &Default::default(),
+    )
+    .await
+    {
+        Ok(_) => {}
+        Err(e) => panic!("{}", e.to_string()),
+    }
+}
+
+#[tokio::test]
+#[should_panic(expected = "Package 'alpha' has no binary named 'beta'. Candidates: alpha")]
+async fn workspace_missing_bin() {
+    let (tx, _) = tokio::sync::mpsc::channel::<String>(256);
+    let project_path = format!("{}/tests/resources/workspace", env!("CARGO_MANIFEST_DIR"));
+    let selection = ServiceSelection {
+        package: Some("alpha".to_string()),
+        bin: Some("beta".to_string()),
+        ..Default::default()
+    };
+    match build_workspace(
+        Path::new(&project_path),
+        None,
+        tx,
+        false,
+        &selection,
+        &Default::default(),
+    )
+    .await
+    {
+        Ok(_) => {}
+        Err(e) => panic!("{}", e.to_string()),
+    }
+}
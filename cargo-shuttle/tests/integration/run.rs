@@ -36,13 +36,19 @@ async fn shuttle_run(working_directory: &str, external: bool) -> String {
             },
             offline: false,
             debug: false,
+            output_mode: Default::default(),
             cmd: Command::Run(RunArgs {
                 port,
                 external,
                 release: false,
+                profile: None,
                 raw: false,
                 bacon: false,
+                all: false,
+                service_args: Default::default(),
+                feature_args: Default::default(),
                 secret_args: Default::default(),
+                otel_args: Default::default(),
             }),
         },
         false,
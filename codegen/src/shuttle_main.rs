@@ -190,6 +190,53 @@ impl LoaderAndRunner {
     }
 }
 
+impl Input {
+    /// Whether this is a `#[shuttle_runtime::Secrets]` input with a user type to deserialize the secrets into
+    fn is_typed_secrets(&self) -> bool {
+        let last_ident = |path: &Path| path.segments.last().map(|s| s.ident.to_string());
+        // other resource crates can have builders named `Secrets` too
+        let is_secrets_builder = self
+            .builder
+            .path
+            .segments
+            .iter()
+            .map(|s| s.ident.to_string())
+            .eq(["shuttle_runtime", "Secrets"]);
+        let is_secret_store = match &self.ty {
+            Type::Path(TypePath { path, .. }) => last_ident(path).as_deref() == Some("SecretStore"),
+            _ => false,
+        };
+
+        is_secrets_builder && !is_secret_store
+    }
+
+    /// Statement that turns the builder output `x` into this input's variable
+    fn init_tokens(&self) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        let ty = &self.ty;
+        let builder = &self.builder.path;
+
+        if self.is_typed_secrets() {
+            quote! {
+                let #ident: #ty = {
+                    let secrets: ::shuttle_runtime::SecretStore = x.into_resource()
+                        .await
+                        .context(format!("failed to initialize {}", stringify!(#builder)))?;
+                    secrets
+                        .deserialize()
+                        .context(format!("failed to deserialize secrets into {}", stringify!(#ty)))?
+                };
+            }
+        } else {
+            quote! {
+                let #ident: #ty = x.into_resource()
+                    .await
+                    .context(format!("failed to initialize {}", stringify!(#builder)))?;
+            }
+        }
+    }
+}
+
 impl ToTokens for LoaderAndRunner {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let fn_ident = &self.fn_ident;
@@ -199,7 +246,7 @@ impl ToTokens for LoaderAndRunner {
         let mut fn_inputs = Vec::with_capacity(inputs_len);
         let mut fn_input_builders = Vec::with_capacity(inputs_len);
         let mut fn_input_builder_options = Vec::with_capacity(inputs_len);
-        let mut fn_input_types = Vec::with_capacity(inputs_len);
+        let mut fn_input_inits = Vec::with_capacity(inputs_len);
 
         // whether any string literals are being used in resource macro args (for secret interpolation)
         let mut needs_vars = false;
@@ -207,7 +254,7 @@ impl ToTokens for LoaderAndRunner {
         for input in self.fn_inputs.iter() {
             fn_inputs.push(&input.ident);
             fn_input_builders.push(&input.builder.path);
-            fn_input_types.push(&input.ty);
+            fn_input_inits.push(input.init_tokens());
 
             let (methods, values): (Vec<_>, Vec<_>) = input
                 .builder
@@ -296,9 +343,7 @@ impl ToTokens for LoaderAndRunner {
                             &iter.next().expect("resource list to have correct length")
                         )
                         .context(format!("failed to deserialize output for {}", stringify!(#fn_input_builders)))?;
-                    let #fn_inputs: #fn_input_types = x.into_resource()
-                        .await
-                        .context(format!("failed to initialize {}", stringify!(#fn_input_builders)))?;
+                    #fn_input_inits
                 )*
 
                 #fn_ident(#(#fn_inputs),*).await
@@ -548,6 +593,52 @@ mod tests {
         assert_eq!(actual.to_string(), expected.to_string());
     }
 
+    #[test]
+    fn typed_secrets_input() {
+        let input = Input {
+            ident: parse_quote!(config),
+            builder: BuilderPattern {
+                path: parse_quote!(shuttle_runtime::Secrets),
+                options: Default::default(),
+            },
+            ty: parse_quote!(MyConfig),
+        };
+        assert!(input.is_typed_secrets());
+
+        let actual = input.init_tokens();
+        let expected = quote! {
+            let config: MyConfig = {
+                let secrets: ::shuttle_runtime::SecretStore = x.into_resource()
+                    .await
+                    .context(format!("failed to initialize {}", stringify!(shuttle_runtime::Secrets)))?;
+                secrets
+                    .deserialize()
+                    .context(format!("failed to deserialize secrets into {}", stringify!(MyConfig)))?
+            };
+        };
+        assert_eq!(actual.to_string(), expected.to_string());
+
+        let store = Input {
+            ident: parse_quote!(secrets),
+            builder: BuilderPattern {
+                path: parse_quote!(shuttle_runtime::Secrets),
+                options: Default::default(),
+            },
+            ty: parse_quote!(shuttle_runtime::SecretStore),
+        };
+        assert!(!store.is_typed_secrets());
+
+        let other_secrets = Input {
+            ident: parse_quote!(config),
+            builder: BuilderPattern {
+                path: parse_quote!(other_crate::Secrets),
+                options: Default::default(),
+            },
+            ty: parse_quote!(MyConfig),
+        };
+        assert!(!other_secrets.is_typed_secrets());
+    }
+
     #[test]
     fn output_with_input_options() {
         let mut input = LoaderAndRunner {
@@ -99,6 +99,41 @@ impl std::fmt::Debug for DatabaseInfo {
     }
 }
 
+/// The input given to Shuttle object storage resources
+#[derive(Clone, Deserialize, Serialize, Default)]
+pub struct BucketInput {
+    /// Override the default bucket name, which is the project name
+    pub bucket_name: Option<String>,
+}
+
+/// The output produced by Shuttle object storage resources:
+/// a bucket and the credentials for accessing it through an S3-compatible API.
+#[derive(Clone, Serialize, Deserialize)]
+#[typeshare::typeshare]
+pub struct S3Config {
+    pub bucket: String,
+    pub region: String,
+    /// The endpoint of the S3-compatible API, such as a local MinIO container.
+    /// Is None when the bucket is on AWS S3.
+    pub endpoint: Option<String>,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Set when the credentials are temporary
+    pub session_token: Option<String>,
+}
+
+// Don't leak credentials in Debug
+impl std::fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("S3Config")
+            .field("bucket", &self.bucket)
+            .field("region", &self.region)
+            .field("endpoint", &self.endpoint)
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Used to request a container from the local run provisioner
 #[derive(Serialize, Deserialize)]
 pub struct ContainerRequest {
@@ -111,6 +146,9 @@ pub struct ContainerRequest {
     pub port: String,
     /// list of "KEY=value" strings
     pub env: Vec<String>,
+    /// Overrides the command of the image
+    #[serde(default)]
+    pub cmd: Option<Vec<String>>,
 }
 
 /// Response from requesting a container from the local run provisioner
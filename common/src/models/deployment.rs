@@ -121,8 +121,9 @@ pub struct UploadArchiveResponse {
 #[typeshare::typeshare]
 pub enum DeploymentRequest {
     /// Build an image from the source code in an attached zip archive
-    BuildArchive(DeploymentRequestBuildArchive),
-    // TODO?: Add GitRepo(DeploymentRequestGitRepo)
+    BuildArchive(Box<DeploymentRequestBuildArchive>),
+    /// Build an image from the source code in a git repository
+    GitRepo(Box<DeploymentRequestGitRepo>),
     /// Use this image directly. Can be used to skip the build step.
     Image(DeploymentRequestImage),
 }
@@ -140,6 +141,21 @@ pub struct DeploymentRequestBuildArchive {
     pub build_meta: Option<BuildMeta>,
 }
 
+#[derive(Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[typeshare::typeshare]
+pub struct DeploymentRequestGitRepo {
+    /// The URL of the git repository to clone
+    pub repo_url: String,
+    /// The branch, tag, or commit to build. Uses the default branch if not set.
+    pub git_ref: Option<String>,
+    /// The path of the Cargo workspace in the repository, if not in the root
+    pub subdirectory: Option<String>,
+    pub build_args: Option<BuildArgs>,
+    /// Secrets to add before this deployment.
+    pub secrets: Option<HashMap<String, String>>,
+}
+
 #[derive(Deserialize, Serialize, Default)]
 #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[serde(tag = "type", content = "content")]
@@ -170,6 +186,8 @@ pub struct BuildArgsRust {
     pub no_default_features: bool,
     /// Use the mold linker
     pub mold: bool,
+    /// Cargo profile to build with (release if not set)
+    pub profile: Option<String>,
 }
 
 impl Default for BuildArgsRust {
@@ -183,6 +201,7 @@ impl Default for BuildArgsRust {
             features: Default::default(),
             no_default_features: Default::default(),
             mold: Default::default(),
+            profile: Default::default(),
         }
     }
 }
@@ -45,6 +45,25 @@ pub struct LogsResponse {
     pub logs: Vec<LogItem>,
 }
 
+/// Filters and ranges to apply to a logs request.
+/// All fields are optional, and an empty query returns all log lines.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[typeshare::typeshare]
+pub struct LogsQuery {
+    /// Only return the first N matching lines
+    pub head: Option<u32>,
+    /// Only return the last N matching lines
+    pub tail: Option<u32>,
+    /// Only return lines logged at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only return lines logged before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Only return lines from this log source, for example "app" or "build"
+    pub source: Option<String>,
+    /// Only return lines containing this substring
+    pub grep: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg_attr(not(feature = "display"), allow(unused_imports))]
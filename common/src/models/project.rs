@@ -49,6 +49,15 @@ impl ProjectResponse {
             self.team_id.as_deref().unwrap_or("N/A")
         )
         .unwrap();
+        writeln!(
+            &mut s,
+            "  Compute Tier: {}",
+            self.compute_tier
+                .map(|t| t.to_string())
+                .as_deref()
+                .unwrap_or("N/A")
+        )
+        .unwrap();
         writeln!(
             &mut s,
             "  Created: {}",
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -11,14 +13,37 @@ pub struct ProvisionResourceRequest {
     pub config: Value,
 }
 
+/// Changes to make to the secrets of a project.
+/// Keys in both `set` and `unset` are removed.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[typeshare::typeshare]
+pub struct SecretsUpdateRequest {
+    /// Secrets to add or overwrite
+    #[typeshare(serialized_as = "HashMap<String, String>")]
+    pub set: BTreeMap<String, String>,
+    /// Keys of secrets to remove
+    pub unset: Vec<String>,
+}
+
 /// Helper for deserializing
 #[derive(Deserialize)]
 #[serde(untagged)] // Try deserializing as a Shuttle resource, fall back to a custom value
 pub enum ResourceInput {
     Shuttle(ProvisionResourceRequest),
+    ShuttleWithData(ResourceWithData<ProvisionResourceRequest>),
     Custom(Value),
 }
 
+/// A resource request or output together with data that the resource itself needs after provisioning,
+/// such as options that the provisioner has no use for.
+///
+/// When a request is wrapped in this, the runtime wraps the provisioned output with the same data.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResourceWithData<T, D = Value> {
+    pub resource: T,
+    pub data: D,
+}
+
 /// The resource state represents the stage of the provisioning process the resource is in.
 #[derive(
     Debug, Clone, PartialEq, Eq, strum::Display, strum::EnumString, Serialize, Deserialize,
@@ -79,6 +104,14 @@ pub enum ResourceType {
     #[strum(to_string = "database::aws_rds::mariadb")]
     #[serde(rename = "database::aws_rds::mariadb")]
     DatabaseAwsRdsMariaDB,
+    /// Redis (or Valkey) in a shared cluster
+    #[strum(to_string = "database::shared::redis")]
+    #[serde(rename = "database::shared::redis")]
+    DatabaseSharedRedis,
+    /// Object storage bucket with an S3-compatible API
+    #[strum(to_string = "storage::bucket")]
+    #[serde(rename = "storage::bucket")]
+    StorageBucket,
     /// (Will probably be removed)
     #[strum(to_string = "secrets")]
     #[serde(rename = "secrets")]
@@ -99,6 +132,8 @@ mod test {
     fn to_string_and_back() {
         let inputs = [
             ResourceType::DatabaseSharedPostgres,
+            ResourceType::DatabaseSharedRedis,
+            ResourceType::StorageBucket,
             ResourceType::Secrets,
             ResourceType::Container,
         ];
@@ -108,4 +143,23 @@ mod test {
             assert_eq!(input, actual, ":{} should map back to itself", input);
         }
     }
+
+    #[test]
+    fn resource_input_with_data() {
+        let request = serde_json::json!({
+            "resource": { "type": "database::shared::postgres", "config": {} },
+            "data": "migrations",
+        });
+        let ResourceInput::ShuttleWithData(input) = serde_json::from_value(request).unwrap() else {
+            panic!("expected a request with data");
+        };
+        assert_eq!(input.resource.r#type, ResourceType::DatabaseSharedPostgres);
+        assert_eq!(input.data, "migrations");
+
+        let output = serde_json::json!({ "resource": "postgres://localhost", "data": null });
+        assert!(matches!(
+            serde_json::from_value(output).unwrap(),
+            ResourceInput::Custom(_)
+        ));
+    }
 }
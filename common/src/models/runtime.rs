@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use super::resource::ResourceType;
+
+/// The phase of the runtime lifecycle that an exit happened in
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RuntimePhase {
+    /// Getting the secrets of the project
+    Secrets,
+    /// Running the resource builders in the user's main function attributes
+    Loading,
+    /// Provisioning the requested resources
+    Provisioning,
+    /// Turning the provisioned resources into the main function inputs and running it
+    ResourceInit,
+    /// Running the service
+    Running,
+    /// Serving the health checks
+    HealthCheck,
+}
+
+/// Why the runtime exited before the service stopped on its own
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RuntimeExitReason {
+    /// The service returned an error from `bind`
+    BindFailed,
+    /// A termination signal was received
+    Interrupted,
+    /// The secrets of the project could not be fetched
+    SecretsFailed,
+    /// A resource builder failed
+    LoaderFailed,
+    /// A resource builder produced an invalid resource request
+    InvalidResourceInput,
+    /// Requesting a resource failed
+    ProvisioningFailed,
+    /// A resource ended up in a state it does not recover from
+    ProvisioningBadState,
+    /// A resource was not ready within the provisioning timeout
+    ProvisioningTimedOut,
+    /// Turning the provisioned resources into the main function inputs, or the main function, failed
+    ResourceInitFailed,
+    /// Serving a health check failed
+    HealthCheckFailed,
+    /// The health check port could not be bound
+    HealthCheckBindFailed,
+    /// Accepting a health check connection failed
+    HealthCheckListenerFailed,
+    /// A reason from a newer runtime version
+    #[serde(other)]
+    Unknown,
+}
+
+/// Why the runtime exited, printed as the final line of its output.
+///
+/// The line is a JSON object with this struct under the [`RuntimeExitReport::KEY`] key,
+/// so that it can be told apart from the service's own output.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RuntimeExitReport {
+    /// The process exit code
+    pub code: i32,
+    pub reason: RuntimeExitReason,
+    pub phase: RuntimePhase,
+    /// The resource that the exit is related to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<ResourceType>,
+    /// The error and its causes, outermost first
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+impl RuntimeExitReport {
+    pub const KEY: &'static str = "shuttle_runtime_exit";
+
+    /// The report as a single JSON line
+    pub fn to_line(&self) -> String {
+        serde_json::json!({ Self::KEY: self }).to_string()
+    }
+
+    /// Parse a report from a line of runtime output, or None if it is a different line
+    pub fn from_line(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if !line.starts_with('{') || !line.contains(Self::KEY) {
+            return None;
+        }
+        let mut value = serde_json::from_str::<serde_json::Value>(line).ok()?;
+
+        serde_json::from_value(value.get_mut(Self::KEY)?.take()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_report_line_roundtrip() {
+        let report = RuntimeExitReport {
+            code: 133,
+            reason: RuntimeExitReason::ProvisioningTimedOut,
+            phase: RuntimePhase::Provisioning,
+            resource_type: Some(ResourceType::DatabaseSharedPostgres),
+            errors: vec!["not ready within 900s".to_owned()],
+        };
+        let line = report.to_line();
+        assert!(line.starts_with(r#"{"shuttle_runtime_exit":{"#));
+        assert!(line.contains(r#""reason":"provisioning_timed_out""#));
+        assert!(!line.contains('\n'));
+        assert_eq!(RuntimeExitReport::from_line(&line), Some(report));
+
+        assert_eq!(RuntimeExitReport::from_line("INFO Starting service"), None);
+        assert_eq!(
+            RuntimeExitReport::from_line(r#"{"shuttle_runtime_exit":1}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn exit_report_unknown_reason() {
+        let report = RuntimeExitReport::from_line(
+            r#"{"shuttle_runtime_exit":{"code":250,"reason":"something_new","phase":"running"}}"#,
+        )
+        .unwrap();
+        assert_eq!(report.reason, RuntimeExitReason::Unknown);
+    }
+}
@@ -63,3 +63,10 @@ pub struct AddTeamMemberRequest {
     /// Role of the user in the team
     pub role: Option<TeamRole>,
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[typeshare::typeshare]
+pub struct UpdateTeamMemberRequest {
+    /// New role of the user in the team
+    pub role: TeamRole,
+}
@@ -1,7 +1,9 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeMap};
 
 use serde::{Deserialize, Serialize};
 
+use crate::secrets::Secret;
+
 const fn default_betterstack_host() -> Cow<'static, str> {
     Cow::Borrowed("in-otel.logs.betterstack.com")
 }
@@ -11,16 +13,17 @@ const fn default_betterstack_host() -> Cow<'static, str> {
 #[typeshare::typeshare]
 pub struct TelemetrySinkStatus {
     /// Indicates that the associated project is configured to export telemetry data to this sink
-    enabled: bool,
+    pub enabled: bool,
 }
 
 /// A safe-for-display representation of the current telemetry export configuration for a given project
 #[derive(Eq, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 #[typeshare::typeshare]
 pub struct TelemetryConfigResponse {
-    betterstack: Option<TelemetrySinkStatus>,
-    datadog: Option<TelemetrySinkStatus>,
-    grafana_cloud: Option<TelemetrySinkStatus>,
+    pub betterstack: Option<TelemetrySinkStatus>,
+    pub datadog: Option<TelemetrySinkStatus>,
+    pub grafana_cloud: Option<TelemetrySinkStatus>,
+    pub otlp: Option<TelemetrySinkStatus>,
 }
 
 impl From<Vec<TelemetrySinkConfig>> for TelemetryConfigResponse {
@@ -39,6 +42,9 @@ impl From<Vec<TelemetrySinkConfig>> for TelemetryConfigResponse {
                 TelemetrySinkConfig::GrafanaCloud(_) => {
                     instance.grafana_cloud = Some(TelemetrySinkStatus { enabled: true })
                 }
+                TelemetrySinkConfig::Otlp(_) => {
+                    instance.otlp = Some(TelemetrySinkStatus { enabled: true })
+                }
             }
         }
 
@@ -81,6 +87,9 @@ pub enum TelemetrySinkConfig {
     /// [Grafana Cloud](https://grafana.com/docs/grafana-cloud/send-data/otlp/)
     GrafanaCloud(GrafanaCloudConfig),
 
+    /// Any [OTLP](https://opentelemetry.io/docs/specs/otlp/) collector
+    Otlp(OtlpConfig),
+
     /// Internal Debugging
     #[doc(hidden)]
     #[typeshare(skip)]
@@ -106,14 +115,14 @@ impl TelemetrySinkConfigDiscriminants {
 pub struct BetterstackConfig {
     #[serde(default = "default_betterstack_host")]
     pub ingesting_host: Cow<'static, str>,
-    pub source_token: String,
+    pub source_token: Secret<String>,
 }
 
 #[cfg(any(test, feature = "integration-tests"))]
 impl Default for BetterstackConfig {
     fn default() -> Self {
         Self {
-            source_token: "some-source-token".into(),
+            source_token: Secret::new("some-source-token".into()),
             ingesting_host: default_betterstack_host(),
         }
     }
@@ -123,14 +132,14 @@ impl Default for BetterstackConfig {
 #[cfg_attr(feature = "integration-tests", derive(Debug))]
 #[typeshare::typeshare]
 pub struct DatadogConfig {
-    pub api_key: String,
+    pub api_key: Secret<String>,
 }
 
 #[cfg(any(test, feature = "integration-tests"))]
 impl Default for DatadogConfig {
     fn default() -> Self {
         Self {
-            api_key: "some-api-key".into(),
+            api_key: Secret::new("some-api-key".into()),
         }
     }
 }
@@ -139,7 +148,7 @@ impl Default for DatadogConfig {
 #[cfg_attr(feature = "integration-tests", derive(Debug))]
 #[typeshare::typeshare]
 pub struct GrafanaCloudConfig {
-    pub token: String,
+    pub token: Secret<String>,
     pub endpoint: String,
     pub instance_id: String,
 }
@@ -148,13 +157,38 @@ pub struct GrafanaCloudConfig {
 impl Default for GrafanaCloudConfig {
     fn default() -> Self {
         Self {
-            token: "some-auth-token".into(),
+            token: Secret::new("some-auth-token".into()),
             instance_id: String::from("0000000"),
             endpoint: "https://prometheus-env-id-env-region.grafana.net/api/prom/push".into(),
         }
     }
 }
 
+#[derive(Eq, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "integration-tests", derive(Debug))]
+#[typeshare::typeshare]
+pub struct OtlpConfig {
+    /// Base URL of the collector's OTLP/HTTP endpoint, such as `https://otel.example.com:4318`
+    pub endpoint: String,
+    /// Headers to send with every export request, such as API keys
+    #[serde(default)]
+    #[typeshare(serialized_as = "HashMap<String, String>")]
+    pub headers: BTreeMap<String, Secret<String>>,
+}
+
+#[cfg(any(test, feature = "integration-tests"))]
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://otel.example.com:4318".into(),
+            headers: BTreeMap::from([(
+                "authorization".into(),
+                Secret::new("Bearer some-token".into()),
+            )]),
+        }
+    }
+}
+
 #[cfg(feature = "integration-tests")]
 impl From<BetterstackConfig> for TelemetrySinkConfig {
     fn from(value: BetterstackConfig) -> Self {
@@ -176,6 +210,13 @@ impl From<GrafanaCloudConfig> for TelemetrySinkConfig {
     }
 }
 
+#[cfg(feature = "integration-tests")]
+impl From<OtlpConfig> for TelemetrySinkConfig {
+    fn from(value: OtlpConfig) -> Self {
+        TelemetrySinkConfig::Otlp(value)
+    }
+}
+
 #[cfg(feature = "integration-tests")]
 impl std::str::FromStr for TelemetrySinkConfig {
     type Err = serde_json::Error;
@@ -208,6 +249,15 @@ impl std::str::FromStr for TelemetrySinkConfig {
                         "cannot deserialize config as valid GrafanaCloud configuration",
                     )
                 }))
+            .or(serde_json::from_str::<OtlpConfig>(config)
+                .map(Self::from)
+                .inspect_err(|error| {
+                    tracing::debug!(
+                        %config,
+                        %error,
+                        "cannot deserialize config as valid OTLP configuration",
+                    )
+                }))
             .map_err(|_| {
                 <serde_json::Error as serde::de::Error>::custom(format!(
                     "configuration does not match any known external telemetry sink: {}",
@@ -221,6 +271,28 @@ impl std::str::FromStr for TelemetrySinkConfig {
 mod tests {
     use super::*;
 
+    #[test]
+    fn sink_config_serializes_secrets_as_strings() {
+        let config = TelemetrySinkConfig::Otlp(OtlpConfig::default());
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            serde_json::json!({
+                "type": "otlp",
+                "content": {
+                    "endpoint": "https://otel.example.com:4318",
+                    "headers": { "authorization": "Bearer some-token" },
+                },
+            })
+        );
+
+        let config: TelemetrySinkConfig = serde_json::from_value(serde_json::json!({
+            "type": "datadog",
+            "content": { "api_key": "some-api-key" },
+        }))
+        .unwrap();
+        assert!(config == TelemetrySinkConfig::Datadog(DatadogConfig::default()));
+    }
+
     #[test]
     fn sink_config_enum() {
         for variant in <TelemetrySinkConfig as strum::IntoEnumIterator>::iter() {
@@ -240,6 +312,10 @@ mod tests {
                         sink.as_db_type()
                     );
                 }
+                sink @ TelemetrySinkConfig::Otlp(_) => {
+                    assert_eq!("otlp", sink.as_ref());
+                    assert_eq!("project::telemetry::otlp::config", sink.as_db_type());
+                }
                 sink @ TelemetrySinkConfig::Debug(_) => {
                     assert_eq!("debug", sink.as_ref());
                     assert_eq!("project::telemetry::debug::config", sink.as_db_type());
@@ -270,6 +346,10 @@ mod tests {
                         serde_json::to_string(&discriminant).unwrap()
                     );
                 }
+                discriminant @ TelemetrySinkConfigDiscriminants::Otlp => {
+                    assert_eq!("otlp", discriminant.as_ref());
+                    assert_eq!(r#""otlp""#, serde_json::to_string(&discriminant).unwrap());
+                }
                 discriminant @ TelemetrySinkConfigDiscriminants::Debug => {
                     assert_eq!("debug", discriminant.as_ref());
                     assert_eq!(r#""debug""#, serde_json::to_string(&discriminant).unwrap());
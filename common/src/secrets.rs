@@ -1,7 +1,10 @@
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{self, DeserializeOwned, IntoDeserializer},
+    forward_to_deserialize_any, Deserialize, Serialize,
+};
 use std::{
-    collections::{BTreeMap, HashMap},
-    fmt::Debug,
+    collections::{btree_map, BTreeMap, HashMap},
+    fmt::{Debug, Display},
 };
 use zeroize::Zeroize;
 
@@ -71,6 +74,351 @@ impl SecretStore {
     pub fn get(&self, key: &str) -> Option<String> {
         self.secrets.get(key).map(|s| s.expose().to_owned())
     }
+
+    /// Deserialize the secrets into a type, such as a config struct.
+    ///
+    /// Dotted keys (`database.url`, also from TOML tables) map to nested structs,
+    /// values are parsed into the type of their field,
+    /// and sequences are given with indexed keys (`hosts.0`, `hosts.1`, also from TOML arrays).
+    ///
+    /// ```
+    /// # use std::collections::BTreeMap;
+    /// # use shuttle_common::secrets::SecretStore;
+    /// #[derive(serde::Deserialize)]
+    /// struct Config {
+    ///     api_key: String,
+    ///     database: Database,
+    ///     hosts: Vec<String>,
+    /// }
+    /// #[derive(serde::Deserialize)]
+    /// struct Database {
+    ///     url: String,
+    ///     pool_size: u32,
+    /// }
+    ///
+    /// let secrets = SecretStore::new(BTreeMap::from([
+    ///     ("api_key".to_owned(), "abc".to_owned().into()),
+    ///     ("database.url".to_owned(), "postgres://localhost".to_owned().into()),
+    ///     ("database.pool_size".to_owned(), "5".to_owned().into()),
+    ///     ("hosts.0".to_owned(), "a.com".to_owned().into()),
+    ///     ("hosts.1".to_owned(), "b.com".to_owned().into()),
+    /// ]));
+    /// let config: Config = secrets.deserialize().unwrap();
+    /// assert_eq!(config.database.pool_size, 5);
+    /// assert_eq!(config.hosts, ["a.com", "b.com"]);
+    /// ```
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, SecretsDeserializeError> {
+        let mut root = BTreeMap::new();
+        for (key, value) in &self.secrets {
+            let mut table = &mut root;
+            let mut parts = key.split('.').peekable();
+            while let Some(part) = parts.next() {
+                if parts.peek().is_none() {
+                    table.insert(
+                        part.to_owned(),
+                        SecretNode::Value(value.expose().to_owned()),
+                    );
+                    break;
+                }
+                let node = table
+                    .entry(part.to_owned())
+                    .or_insert_with(|| SecretNode::Table(BTreeMap::new()));
+                table = match node {
+                    SecretNode::Table(table) => table,
+                    SecretNode::Value(_) => {
+                        return Err(SecretsDeserializeError {
+                            path: vec![key.to_owned()],
+                            kind: SecretsDeserializeErrorKind::Invalid(
+                                "conflicts with a value at a parent key".to_owned(),
+                            ),
+                        })
+                    }
+                };
+            }
+        }
+
+        T::deserialize(SecretNode::Table(root))
+    }
+}
+
+/// Error from [`SecretStore::deserialize`], naming the secret that is missing or could not be parsed
+#[derive(Debug)]
+pub struct SecretsDeserializeError {
+    /// Path to the secret, innermost key first
+    path: Vec<String>,
+    kind: SecretsDeserializeErrorKind,
+}
+
+#[derive(Debug)]
+enum SecretsDeserializeErrorKind {
+    Missing,
+    Invalid(String),
+}
+
+impl SecretsDeserializeError {
+    /// The (dotted) key of the secret, if known
+    pub fn key(&self) -> Option<String> {
+        if self.path.is_empty() {
+            return None;
+        }
+        Some(
+            self.path
+                .iter()
+                .rev()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join("."),
+        )
+    }
+}
+
+impl Display for SecretsDeserializeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.kind, self.key()) {
+            (SecretsDeserializeErrorKind::Missing, Some(key)) => {
+                write!(f, "missing secret '{key}'")
+            }
+            (SecretsDeserializeErrorKind::Missing, None) => write!(f, "missing secret"),
+            (SecretsDeserializeErrorKind::Invalid(msg), Some(key)) => {
+                write!(f, "invalid secret '{key}': {msg}")
+            }
+            (SecretsDeserializeErrorKind::Invalid(msg), None) => {
+                write!(f, "invalid secrets: {msg}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SecretsDeserializeError {}
+
+impl de::Error for SecretsDeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self {
+            path: Vec::new(),
+            kind: SecretsDeserializeErrorKind::Invalid(msg.to_string()),
+        }
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self {
+            path: vec![field.to_owned()],
+            kind: SecretsDeserializeErrorKind::Missing,
+        }
+    }
+}
+
+/// A secret value or a table of secrets under a common dotted prefix
+enum SecretNode {
+    Value(String),
+    Table(BTreeMap<String, SecretNode>),
+}
+
+impl SecretNode {
+    fn parse<T: std::str::FromStr>(self) -> Result<T, SecretsDeserializeError>
+    where
+        T::Err: Display,
+    {
+        match self {
+            SecretNode::Value(value) => value.parse().map_err(de::Error::custom),
+            SecretNode::Table(_) => Err(de::Error::custom("expected a value, found a table")),
+        }
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for SecretNode {
+    type Error = SecretsDeserializeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            SecretNode::Value(value) => visitor.visit_string(value),
+            SecretNode::Table(table) => visitor.visit_map(SecretTableAccess {
+                iter: table.into_iter(),
+                next: None,
+            }),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let SecretNode::Table(table) = self else {
+            return Err(de::Error::custom(
+                "expected an array with indexed keys, found a value",
+            ));
+        };
+
+        let mut items = Vec::with_capacity(table.len());
+        for (key, node) in table {
+            let Ok(index) = key.parse::<usize>() else {
+                return Err(SecretsDeserializeError {
+                    path: vec![key],
+                    kind: SecretsDeserializeErrorKind::Invalid(
+                        "expected an array index".to_owned(),
+                    ),
+                });
+            };
+            items.push((index, node));
+        }
+        // keys are sorted as strings, so "10" comes before "2"
+        items.sort_by_key(|(index, _)| *index);
+        if let Some((expected, _)) = items
+            .iter()
+            .enumerate()
+            .find(|(expected, (index, _))| expected != index)
+        {
+            return Err(SecretsDeserializeError {
+                path: vec![expected.to_string()],
+                kind: SecretsDeserializeErrorKind::Missing,
+            });
+        }
+
+        visitor.visit_seq(SecretSeqAccess {
+            iter: items.into_iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self {
+            SecretNode::Value(value) => visitor.visit_enum(value.into_deserializer()),
+            SecretNode::Table(_) => Err(de::Error::custom("expected a value, found a table")),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct map struct identifier ignored_any
+    }
+}
+
+impl IntoDeserializer<'_, SecretsDeserializeError> for SecretNode {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+struct SecretTableAccess {
+    iter: btree_map::IntoIter<String, SecretNode>,
+    next: Option<(String, SecretNode)>,
+}
+
+impl<'de> de::MapAccess<'de> for SecretTableAccess {
+    type Error = SecretsDeserializeError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, node)) = self.iter.next() else {
+            return Ok(None);
+        };
+        let key_value = seed.deserialize(key.clone().into_deserializer())?;
+        self.next = Some((key, node));
+
+        Ok(Some(key_value))
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (key, node) = self
+            .next
+            .take()
+            .expect("next_value_seed to be called after next_key_seed");
+
+        // name the secret that failed, and prefix the ones from nested tables
+        seed.deserialize(node).map_err(|mut e| {
+            e.path.push(key);
+            e
+        })
+    }
+}
+
+struct SecretSeqAccess {
+    iter: std::vec::IntoIter<(usize, SecretNode)>,
+}
+
+impl<'de> de::SeqAccess<'de> for SecretSeqAccess {
+    type Error = SecretsDeserializeError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        let Some((index, node)) = self.iter.next() else {
+            return Ok(None);
+        };
+
+        // name the element that failed
+        seed.deserialize(node).map(Some).map_err(|mut e| {
+            e.path.push(index.to_string());
+            e
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
 }
 
 impl IntoIterator for SecretStore {
@@ -131,6 +479,142 @@ mod secrets_tests {
         );
     }
 
+    #[test]
+    fn secretstore_deserialize() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Config {
+            api_key: String,
+            port: u16,
+            debug: bool,
+            hosts: Vec<String>,
+            optional: Option<String>,
+            database: Database,
+        }
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Database {
+            url: String,
+        }
+
+        let ss = SecretStore::new(BTreeMap::from([
+            ("api_key".to_owned(), "abc".to_owned().into()),
+            ("port".to_owned(), "8080".to_owned().into()),
+            ("debug".to_owned(), "true".to_owned().into()),
+            ("hosts.0".to_owned(), "a.com".to_owned().into()),
+            ("hosts.1".to_owned(), "b,c.com".to_owned().into()),
+            ("database.url".to_owned(), "postgres://".to_owned().into()),
+        ]));
+
+        assert_eq!(
+            ss.deserialize::<Config>().unwrap(),
+            Config {
+                api_key: "abc".to_owned(),
+                port: 8080,
+                debug: true,
+                hosts: vec!["a.com".to_owned(), "b,c.com".to_owned()],
+                optional: None,
+                database: Database {
+                    url: "postgres://".to_owned()
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn secretstore_deserialize_errors() {
+        #[derive(Debug, Deserialize)]
+        #[allow(unused)]
+        struct Config {
+            database: Database,
+        }
+        #[derive(Debug, Deserialize)]
+        #[allow(unused)]
+        struct Database {
+            url: String,
+            pool_size: u32,
+        }
+
+        let missing = SecretStore::new(BTreeMap::from([(
+            "database.pool_size".to_owned(),
+            "5".to_owned().into(),
+        )]));
+        let err = missing.deserialize::<Config>().unwrap_err();
+        assert_eq!(err.key().as_deref(), Some("database.url"));
+        assert_eq!(err.to_string(), "missing secret 'database.url'");
+
+        let wrong_type = SecretStore::new(BTreeMap::from([
+            ("database.url".to_owned(), "postgres://".to_owned().into()),
+            ("database.pool_size".to_owned(), "five".to_owned().into()),
+        ]));
+        let err = wrong_type.deserialize::<Config>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid secret 'database.pool_size': invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn secretstore_deserialize_arrays() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Config {
+            ports: Vec<u16>,
+            servers: Vec<Server>,
+        }
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Server {
+            host: String,
+        }
+
+        let mut secrets = BTreeMap::from([
+            ("servers.0.host".to_owned(), "a.com".to_owned().into()),
+            ("servers.1.host".to_owned(), "b.com".to_owned().into()),
+        ]);
+        // more than 10 elements, to check that indices are ordered as numbers
+        for i in 0..11 {
+            secrets.insert(format!("ports.{i}"), i.to_string().into());
+        }
+
+        assert_eq!(
+            SecretStore::new(secrets.clone())
+                .deserialize::<Config>()
+                .unwrap(),
+            Config {
+                ports: (0..11).collect(),
+                servers: vec![
+                    Server {
+                        host: "a.com".to_owned()
+                    },
+                    Server {
+                        host: "b.com".to_owned()
+                    },
+                ],
+            }
+        );
+
+        secrets.insert("ports.3".to_owned(), "three".to_owned().into());
+        let err = SecretStore::new(secrets.clone())
+            .deserialize::<Config>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid secret 'ports.3': invalid digit found in string"
+        );
+
+        secrets.remove("ports.3");
+        let err = SecretStore::new(secrets)
+            .deserialize::<Config>()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "missing secret 'ports.3'");
+
+        let not_indexed = SecretStore::new(BTreeMap::from([
+            ("ports".to_owned(), "1,2".to_owned().into()),
+            ("servers.0.host".to_owned(), "a.com".to_owned().into()),
+        ]));
+        assert_eq!(
+            not_indexed.deserialize::<Config>().unwrap_err().to_string(),
+            "invalid secret 'ports': expected an array with indexed keys, found a value"
+        );
+    }
+
     #[test]
     fn secretstore_intoiter() {
         let bt = BTreeMap::from([
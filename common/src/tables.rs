@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Local, SecondsFormat};
 use comfy_table::{
     presets::{NOTHING, UTF8_BORDERS_ONLY},
@@ -10,9 +12,11 @@ use crate::{
         deployment::DeploymentResponse,
         project::ProjectResponse,
         resource::{ResourceResponse, ResourceType},
+        team::{TeamMembersResponse, TeamResponse},
+        telemetry::{TelemetryConfigResponse, TelemetrySinkStatus},
     },
     secrets::SecretStore,
-    DatabaseInfo,
+    DatabaseInfo, S3Config,
 };
 
 pub fn get_certificates_table(certs: &[CertificateResponse], raw: bool) -> String {
@@ -64,7 +68,12 @@ pub fn get_projects_table(projects: &[ProjectResponse], raw: bool) -> String {
     table
         .load_preset(if raw { NOTHING } else { UTF8_BORDERS_ONLY })
         .set_content_arrangement(ContentArrangement::Disabled)
-        .set_header(vec!["Project ID", "Project Name", "Deployment Status"]);
+        .set_header(vec![
+            "Project ID",
+            "Project Name",
+            "Compute Tier",
+            "Deployment Status",
+        ]);
 
     for project in projects {
         let state = project
@@ -80,6 +89,12 @@ pub fn get_projects_table(projects: &[ProjectResponse], raw: bool) -> String {
         table.add_row(vec![
             Cell::new(&project.id).add_attribute(Attribute::Bold),
             Cell::new(&project.name),
+            Cell::new(
+                project
+                    .compute_tier
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| "N/A".to_owned()),
+            ),
             Cell::new(state).fg(color),
         ]);
     }
@@ -87,6 +102,109 @@ pub fn get_projects_table(projects: &[ProjectResponse], raw: bool) -> String {
     table.to_string()
 }
 
+pub fn get_telemetry_table(config: &TelemetryConfigResponse, raw: bool) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(if raw { NOTHING } else { UTF8_BORDERS_ONLY })
+        .set_content_arrangement(ContentArrangement::Disabled)
+        .set_header(vec!["Sink", "Status"]);
+
+    for (sink, status) in [
+        ("betterstack", &config.betterstack),
+        ("datadog", &config.datadog),
+        ("grafana_cloud", &config.grafana_cloud),
+        ("otlp", &config.otlp),
+    ] {
+        let enabled = matches!(status, Some(TelemetrySinkStatus { enabled: true }));
+        table.add_row(vec![
+            Cell::new(sink).add_attribute(Attribute::Bold),
+            if enabled {
+                Cell::new("enabled").fg(Color::Green)
+            } else {
+                Cell::new("disabled").fg(Color::DarkGrey)
+            },
+        ]);
+    }
+
+    table.to_string()
+}
+
+pub fn get_teams_table(teams: &[TeamResponse], raw: bool) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(if raw { NOTHING } else { UTF8_BORDERS_ONLY })
+        .set_content_arrangement(ContentArrangement::Disabled)
+        .set_header(vec!["Team ID", "Team Name", "Your Role"]);
+
+    for team in teams {
+        table.add_row(vec![
+            Cell::new(&team.id).add_attribute(Attribute::Bold),
+            Cell::new(&team.name),
+            Cell::new(&team.membership.role),
+        ]);
+    }
+
+    table.to_string()
+}
+
+pub fn get_team_members_table(members: &TeamMembersResponse, raw: bool) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(if raw { NOTHING } else { UTF8_BORDERS_ONLY })
+        .set_content_arrangement(ContentArrangement::Disabled)
+        .set_header(vec!["User ID", "Role"]);
+
+    for member in &members.members {
+        table.add_row(vec![
+            Cell::new(&member.user_id).add_attribute(Attribute::Bold),
+            Cell::new(&member.role),
+        ]);
+    }
+
+    if members.invites.is_empty() {
+        return table.to_string();
+    }
+
+    let mut invites = Table::new();
+    invites
+        .load_preset(if raw { NOTHING } else { UTF8_BORDERS_ONLY })
+        .set_content_arrangement(ContentArrangement::Disabled)
+        .set_header(vec!["Invite ID", "Email", "Role", "Expires"]);
+
+    for invite in &members.invites {
+        let expires: DateTime<Local> = DateTime::from(invite.expires_at);
+        invites.add_row(vec![
+            Cell::new(&invite.id).add_attribute(Attribute::Bold),
+            Cell::new(&invite.email),
+            Cell::new(&invite.role),
+            Cell::new(expires.to_rfc3339_opts(SecondsFormat::Secs, false)),
+        ]);
+    }
+
+    format!("{table}\n\nPending invites\n{invites}")
+}
+
+pub fn get_secrets_list_table(
+    secrets: &BTreeMap<String, String>,
+    raw: bool,
+    show_values: bool,
+) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(if raw { NOTHING } else { UTF8_BORDERS_ONLY })
+        .set_content_arrangement(ContentArrangement::Disabled)
+        .set_header(vec!["Key", "Value"]);
+
+    for (key, value) in secrets {
+        table.add_row(vec![
+            Cell::new(key).add_attribute(Attribute::Bold),
+            Cell::new(if show_values { value } else { "********" }),
+        ]);
+    }
+
+    table.to_string()
+}
+
 pub fn get_resource_tables(
     resources: &[ResourceResponse],
     service_name: &str,
@@ -116,6 +234,7 @@ pub fn get_resource_tables(
                         | ResourceType::DatabaseAwsRdsMariaDB
                         | ResourceType::DatabaseAwsRdsMySql
                         | ResourceType::DatabaseAwsRdsPostgres
+                        | ResourceType::DatabaseSharedRedis
                 )
             })
             .map(Clone::clone)
@@ -124,9 +243,67 @@ pub fn get_resource_tables(
         raw,
         show_secrets,
     ));
+    output.push(get_buckets_table(
+        &resources
+            .iter()
+            .filter(|r| matches!(r.r#type, ResourceType::StorageBucket))
+            .map(Clone::clone)
+            .collect::<Vec<_>>(),
+        service_name,
+        raw,
+        show_secrets,
+    ));
     output.join("\n")
 }
 
+fn get_buckets_table(
+    buckets: &[ResourceResponse],
+    service_name: &str,
+    raw: bool,
+    show_secrets: bool,
+) -> String {
+    if buckets.is_empty() {
+        return String::new();
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(if raw { NOTHING } else { UTF8_BORDERS_ONLY })
+        .set_content_arrangement(ContentArrangement::Disabled)
+        .set_header(vec![
+            "Bucket",
+            "Region",
+            "Endpoint",
+            "Access key ID",
+            "Secret access key",
+        ]);
+
+    for bucket in buckets {
+        let config = serde_json::from_value::<S3Config>(bucket.output.clone())
+            .expect("resource data to be a valid bucket");
+
+        table.add_row(vec![
+            config.bucket,
+            config.region,
+            config.endpoint.unwrap_or_else(|| "AWS S3".to_owned()),
+            config.access_key_id,
+            if show_secrets {
+                config.secret_access_key
+            } else {
+                "********".to_owned()
+            },
+        ]);
+    }
+
+    let show_secret_hint = if show_secrets {
+        ""
+    } else {
+        "Hint: you can show the secrets of these resources using `shuttle resource list --show-secrets`\n"
+    };
+
+    format!("These buckets are linked to {service_name}\n{table}\n{show_secret_hint}")
+}
+
 fn get_databases_table(
     databases: &[ResourceResponse],
     service_name: &str,
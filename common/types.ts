@@ -53,6 +53,8 @@ export interface BuildArgsRust {
 	no_default_features: boolean;
 	/** Use the mold linker */
 	mold: boolean;
+	/** Cargo profile to build with (release if not set) */
+	profile?: string;
 }
 
 export interface BuildMeta {
@@ -139,6 +141,18 @@ export interface DeploymentRequestBuildArchive {
 	build_meta?: BuildMeta;
 }
 
+export interface DeploymentRequestGitRepo {
+	/** The URL of the git repository to clone */
+	repo_url: string;
+	/** The branch, tag, or commit to build. Uses the default branch if not set. */
+	git_ref?: string;
+	/** The path of the Cargo workspace in the repository, if not in the root */
+	subdirectory?: string;
+	build_args?: BuildArgs;
+	/** Secrets to add before this deployment. */
+	secrets?: Record<string, string>;
+}
+
 export interface DeploymentRequestImage {
 	image: string;
 	/** TODO: Remove this in favour of a separate secrets uploading action. */
@@ -158,10 +172,36 @@ export interface LogItem {
 	line: string;
 }
 
+/**
+ * Filters and ranges to apply to a logs request.
+ * All fields are optional, and an empty query returns all log lines.
+ */
+export interface LogsQuery {
+	/** Only return the first N matching lines */
+	head?: number;
+	/** Only return the last N matching lines */
+	tail?: number;
+	/** Only return lines logged at or after this time */
+	since?: string;
+	/** Only return lines logged before this time */
+	until?: string;
+	/** Only return lines from this log source, for example "app" or "build" */
+	source?: string;
+	/** Only return lines containing this substring */
+	grep?: string;
+}
+
 export interface LogsResponse {
 	logs: LogItem[];
 }
 
+export interface OtlpConfig {
+	/** Base URL of the collector's OTLP/HTTP endpoint, such as `https://otel.example.com:4318` */
+	endpoint: string;
+	/** Headers to send with every export request, such as API keys */
+	headers?: Record<string, string>;
+}
+
 export interface ProjectCreateRequest {
 	name: string;
 }
@@ -214,6 +254,10 @@ export enum ResourceType {
 	DatabaseAwsRdsPostgres = "database::aws_rds::postgres",
 	DatabaseAwsRdsMySql = "database::aws_rds::mysql",
 	DatabaseAwsRdsMariaDB = "database::aws_rds::mariadb",
+	/** Redis (or Valkey) in a shared cluster */
+	DatabaseSharedRedis = "database::shared::redis",
+	/** Object storage bucket with an S3-compatible API */
+	StorageBucket = "storage::bucket",
 	/** (Will probably be removed) */
 	Secrets = "secrets",
 	/** Local provisioner only */
@@ -253,6 +297,35 @@ export interface ResourceListResponse {
 	resources: ResourceResponse[];
 }
 
+/**
+ * The output produced by Shuttle object storage resources:
+ * a bucket and the credentials for accessing it through an S3-compatible API.
+ */
+export interface S3Config {
+	bucket: string;
+	region: string;
+	/**
+	 * The endpoint of the S3-compatible API, such as a local MinIO container.
+	 * Is None when the bucket is on AWS S3.
+	 */
+	endpoint?: string;
+	access_key_id: string;
+	secret_access_key: string;
+	/** Set when the credentials are temporary */
+	session_token?: string;
+}
+
+/**
+ * Changes to make to the secrets of a project.
+ * Keys in both `set` and `unset` are removed.
+ */
+export interface SecretsUpdateRequest {
+	/** Secrets to add or overwrite */
+	set: Record<string, string>;
+	/** Keys of secrets to remove */
+	unset: string[];
+}
+
 export enum SubscriptionType {
 	Pro = "pro",
 	Rds = "rds",
@@ -314,6 +387,12 @@ export interface TelemetryConfigResponse {
 	betterstack?: TelemetrySinkStatus;
 	datadog?: TelemetrySinkStatus;
 	grafana_cloud?: TelemetrySinkStatus;
+	otlp?: TelemetrySinkStatus;
+}
+
+export interface UpdateTeamMemberRequest {
+	/** New role of the user in the team */
+	role: TeamRole;
 }
 
 export interface UploadArchiveResponse {
@@ -349,6 +428,8 @@ export interface UserResponse {
 export type DeploymentRequest = 
 	/** Build an image from the source code in an attached zip archive */
 	| { type: "BuildArchive", content: DeploymentRequestBuildArchive }
+	/** Build an image from the source code in a git repository */
+	| { type: "GitRepo", content: DeploymentRequestGitRepo }
 	/** Use this image directly. Can be used to skip the build step. */
 	| { type: "Image", content: DeploymentRequestImage };
 
@@ -359,5 +440,7 @@ export type TelemetrySinkConfig =
 	/** [Datadog](https://docs.datadoghq.com/opentelemetry/collector_exporter/otel_collector_datadog_exporter) */
 	| { type: "datadog", content: DatadogConfig }
 	/** [Grafana Cloud](https://grafana.com/docs/grafana-cloud/send-data/otlp/) */
-	| { type: "grafana_cloud", content: GrafanaCloudConfig };
+	| { type: "grafana_cloud", content: GrafanaCloudConfig }
+	/** Any [OTLP](https://opentelemetry.io/docs/specs/otlp/) collector */
+	| { type: "otlp", content: OtlpConfig };
 
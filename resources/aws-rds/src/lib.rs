@@ -3,7 +3,7 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use shuttle_service::{
-    resource::{ProvisionResourceRequest, ResourceType},
+    resource::{ProvisionResourceRequest, ResourceType, ResourceWithData},
     DatabaseResource, DbInput, Environment, Error, IntoResource, ResourceFactory,
     ResourceInputBuilder,
 };
@@ -17,17 +17,19 @@ use diesel_async::pooled_connection::bb8 as diesel_bb8;
 #[cfg(feature = "diesel-async-deadpool")]
 use diesel_async::pooled_connection::deadpool as diesel_deadpool;
 
+mod migrations;
+
 #[allow(dead_code)]
 const MIN_CONNECTIONS: u32 = 1;
 #[allow(dead_code)]
 const MAX_CONNECTIONS: u32 = 5;
 
-/// Conditionally request a Shuttle resource
+/// Conditionally request a Shuttle resource. Both carry the migrations directory, if any.
 #[derive(Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum MaybeRequest {
-    Request(ProvisionResourceRequest),
-    NotRequest(DatabaseResource),
+    Request(ResourceWithData<ProvisionResourceRequest, Option<String>>),
+    NotRequest(ResourceWithData<DatabaseResource, Option<String>>),
 }
 
 macro_rules! aws_engine {
@@ -36,7 +38,7 @@ macro_rules! aws_engine {
             #[cfg(feature = $feature)]
             #[derive(Default)]
             #[doc = "Shuttle managed AWS RDS " $struct_ident " instance"]
-            pub struct $struct_ident(DbInput);
+            pub struct $struct_ident(DbInput, Option<String>);
 
             #[cfg(feature = $feature)]
             impl $struct_ident {
@@ -53,6 +55,13 @@ macro_rules! aws_engine {
 
                     self
                 }
+
+                /// Run the migrations in this directory when the resource is initialized
+                pub fn migrations(mut self, migrations: &str) -> Self {
+                    self.1 = Some(migrations.to_string());
+
+                    self
+                }
             }
 
             #[cfg(feature = $feature)]
@@ -64,17 +73,24 @@ macro_rules! aws_engine {
                 async fn build(self, factory: &ResourceFactory) -> Result<Self::Input, Error> {
                     let md = factory.get_metadata();
                     Ok(match md.env {
-                        Environment::Deployment => MaybeRequest::Request(ProvisionResourceRequest {
-                            r#type: ResourceType::$res_type,
-                            config: serde_json::to_value(self.0).unwrap(),
-                        }),
-                        Environment::Local => match self.0.local_uri {
-                            Some(local_uri) => {
-                                MaybeRequest::NotRequest(DatabaseResource::ConnectionString(local_uri))
-                            }
-                            None => MaybeRequest::Request(ProvisionResourceRequest {
+                        Environment::Deployment => MaybeRequest::Request(ResourceWithData {
+                            resource: ProvisionResourceRequest {
                                 r#type: ResourceType::$res_type,
                                 config: serde_json::to_value(self.0).unwrap(),
+                            },
+                            data: self.1,
+                        }),
+                        Environment::Local => match self.0.local_uri {
+                            Some(local_uri) => MaybeRequest::NotRequest(ResourceWithData {
+                                resource: DatabaseResource::ConnectionString(local_uri),
+                                data: self.1,
+                            }),
+                            None => MaybeRequest::Request(ResourceWithData {
+                                resource: ProvisionResourceRequest {
+                                    r#type: ResourceType::$res_type,
+                                    config: serde_json::to_value(self.0).unwrap(),
+                                },
+                                data: self.1,
                             }),
                         },
                     })
@@ -88,17 +104,28 @@ aws_engine!("postgres", Postgres, DatabaseAwsRdsPostgres);
 aws_engine!("mysql", MySql, DatabaseAwsRdsMySql);
 aws_engine!("mariadb", MariaDB, DatabaseAwsRdsMariaDB);
 
+/// The database and the migrations directory, if any
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct OutputWrapper(DatabaseResource);
+pub struct OutputWrapper(ResourceWithData<DatabaseResource, Option<String>>);
 
+/// Runs the migrations, if any. The other resource types are built from this one.
 #[async_trait]
 impl IntoResource<String> for OutputWrapper {
     async fn into_resource(self) -> Result<String, Error> {
-        Ok(match self.0 {
+        let ResourceWithData {
+            resource,
+            data: migrations,
+        } = self.0;
+        let connection_string = match resource {
             DatabaseResource::ConnectionString(s) => s,
             DatabaseResource::Info(info) => info.connection_string(true),
-        })
+        };
+        if let Some(dir) = migrations {
+            migrations::run(&connection_string, &dir).await?;
+        }
+
+        Ok(connection_string)
     }
 }
 
@@ -0,0 +1,118 @@
+//! Running migrations when a database resource is initialized
+
+use std::path::Path;
+
+use shuttle_service::Error;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Run the pending migrations in `dir` against the database.
+///
+/// Directories with a `<version>_<name>/up.sql` per migration are run with diesel,
+/// and `<version>_<name>.sql` files are run with sqlx.
+pub(crate) async fn run(connection_string: &str, dir: &str) -> Result<(), Error> {
+    let dir = Path::new(dir);
+    let is_diesel = std::fs::read_dir(dir)
+        .map_err(|e| Error::Database(format!("failed to read migrations in {dir:?}: {e}")))?
+        .filter_map(Result::ok)
+        .any(|entry| entry.path().join("up.sql").is_file());
+    let is_postgres = connection_string.starts_with("postgres");
+
+    if is_diesel {
+        run_diesel(connection_string, dir, is_postgres).await
+    } else {
+        run_sqlx(connection_string, dir, is_postgres).await
+    }
+    .map_err(|e| Error::Database(format!("failed to run migrations in {dir:?}: {e}")))
+}
+
+#[cfg(feature = "sqlx")]
+async fn run_sqlx(connection_string: &str, dir: &Path, is_postgres: bool) -> Result<(), BoxError> {
+    let migrator = sqlx::migrate::Migrator::new(dir).await?;
+
+    if is_postgres {
+        #[cfg(feature = "postgres")]
+        {
+            let pool = sqlx::postgres::PgPoolOptions::new()
+                .max_connections(1)
+                .connect(connection_string)
+                .await?;
+            migrator.run(&pool).await?;
+            pool.close().await;
+
+            return Ok(());
+        }
+    } else {
+        #[cfg(any(feature = "mysql", feature = "mariadb"))]
+        {
+            let pool = sqlx::mysql::MySqlPoolOptions::new()
+                .max_connections(1)
+                .connect(connection_string)
+                .await?;
+            migrator.run(&pool).await?;
+            pool.close().await;
+
+            return Ok(());
+        }
+    }
+
+    #[allow(unreachable_code)]
+    Err("the database engine's feature is not enabled".into())
+}
+
+#[cfg(not(feature = "sqlx"))]
+async fn run_sqlx(_: &str, _: &Path, _: bool) -> Result<(), BoxError> {
+    Err("sqlx migrations need the `sqlx` feature".into())
+}
+
+#[cfg(feature = "diesel-migrations")]
+async fn run_diesel(
+    connection_string: &str,
+    dir: &Path,
+    is_postgres: bool,
+) -> Result<(), BoxError> {
+    use diesel::Connection;
+    use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+    use diesel_migrations::{FileBasedMigrations, MigrationHarness};
+
+    let connection_string = connection_string.to_owned();
+    let dir = dir.to_owned();
+
+    // The wrapper blocks on the async connection, so keep it off the async workers
+    tokio::task::spawn_blocking(move || {
+        let migrations = FileBasedMigrations::from_path(dir)?;
+
+        if is_postgres {
+            #[cfg(feature = "postgres")]
+            {
+                let mut conn =
+                    AsyncConnectionWrapper::<diesel_async::AsyncPgConnection>::establish(
+                        &connection_string,
+                    )?;
+                conn.run_pending_migrations(migrations)?;
+
+                return Ok(());
+            }
+        } else {
+            #[cfg(any(feature = "mysql", feature = "mariadb"))]
+            {
+                let mut conn =
+                    AsyncConnectionWrapper::<diesel_async::AsyncMysqlConnection>::establish(
+                        &connection_string,
+                    )?;
+                conn.run_pending_migrations(migrations)?;
+
+                return Ok(());
+            }
+        }
+
+        #[allow(unreachable_code)]
+        Err::<_, BoxError>("the database engine's feature is not enabled".into())
+    })
+    .await?
+}
+
+#[cfg(not(feature = "diesel-migrations"))]
+async fn run_diesel(_: &str, _: &Path, _: bool) -> Result<(), BoxError> {
+    Err("diesel migrations need the `diesel-migrations` feature".into())
+}
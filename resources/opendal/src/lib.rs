@@ -6,9 +6,12 @@ use opendal::{Operator, Scheme};
 use serde::{Deserialize, Serialize};
 use shuttle_service::{
     error::{CustomError, Error as ShuttleError},
-    IntoResource, ResourceFactory, ResourceInputBuilder,
+    resource::{ProvisionResourceRequest, ResourceType},
+    BucketInput, IntoResource, ResourceFactory, ResourceInputBuilder,
 };
 
+pub use shuttle_service::S3Config;
+
 #[derive(Serialize)]
 pub struct Opendal {
     scheme: String,
@@ -70,6 +73,71 @@ impl IntoResource<Operator> for OpendalOutput {
     }
 }
 
+/// Shuttle managed object storage bucket, accessed through an S3-compatible API.
+///
+/// Locally, the bucket is created in a MinIO container.
+#[derive(Default)]
+pub struct Bucket(BucketInput);
+
+impl Bucket {
+    /// Use a custom bucket name instead of the project name
+    pub fn bucket_name(mut self, bucket_name: &str) -> Self {
+        self.0.bucket_name = Some(bucket_name.to_string());
+
+        self
+    }
+}
+
+#[async_trait]
+impl ResourceInputBuilder for Bucket {
+    type Input = ProvisionResourceRequest;
+    type Output = BucketOutput;
+
+    async fn build(self, _factory: &ResourceFactory) -> Result<Self::Input, ShuttleError> {
+        Ok(ProvisionResourceRequest {
+            r#type: ResourceType::StorageBucket,
+            config: serde_json::to_value(self.0).unwrap(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BucketOutput(S3Config);
+
+#[async_trait]
+impl IntoResource<S3Config> for BucketOutput {
+    async fn into_resource(self) -> Result<S3Config, shuttle_service::Error> {
+        Ok(self.0)
+    }
+}
+
+#[async_trait]
+impl IntoResource<Operator> for BucketOutput {
+    async fn into_resource(self) -> Result<Operator, shuttle_service::Error> {
+        let S3Config {
+            bucket,
+            region,
+            endpoint,
+            access_key_id,
+            secret_access_key,
+            session_token,
+        } = self.0;
+        let cfg = [
+            ("bucket", Some(bucket)),
+            ("region", Some(region)),
+            ("endpoint", endpoint),
+            ("access_key_id", Some(access_key_id)),
+            ("secret_access_key", Some(secret_access_key)),
+            ("session_token", session_token),
+        ]
+        .into_iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v?)));
+
+        Ok(Operator::via_iter(Scheme::S3, cfg).map_err(Error)?)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -119,4 +187,31 @@ mod test {
         let op: Operator = output.into_resource().await.unwrap();
         assert_eq!(op.info().scheme(), Scheme::S3)
     }
+
+    #[tokio::test]
+    async fn bucket_s3() {
+        let factory =
+            ResourceFactory::new(Default::default(), Default::default(), Default::default());
+
+        let request = Bucket::default()
+            .bucket_name("assets")
+            .build(&factory)
+            .await
+            .unwrap();
+        assert_eq!(request.r#type, ResourceType::StorageBucket);
+        assert_eq!(request.config["bucket_name"], "assets");
+
+        let output: BucketOutput = serde_json::from_value(serde_json::json!({
+            "bucket": "assets",
+            "region": "us-east-1",
+            "endpoint": "http://localhost:9000",
+            "access_key_id": "ak",
+            "secret_access_key": "sk",
+            "session_token": null,
+        }))
+        .unwrap();
+        let op: Operator = output.into_resource().await.unwrap();
+        assert_eq!(op.info().scheme(), Scheme::S3);
+        assert_eq!(op.info().name(), "assets");
+    }
 }
@@ -72,6 +72,7 @@ impl ResourceInputBuilder for Qdrant {
                         image: "docker.io/qdrant/qdrant:v1.10.0".to_string(),
                         port: "6334/tcp".to_string(),
                         env: vec![],
+                        cmd: None,
                     })
                     .unwrap(),
                 })),
@@ -0,0 +1,11 @@
+#![doc = include_str!("../README.md")]
+
+#[cfg(feature = "nats")]
+mod nats;
+#[cfg(feature = "rabbitmq")]
+mod rabbitmq;
+
+#[cfg(feature = "nats")]
+pub use nats::*;
+#[cfg(feature = "rabbitmq")]
+pub use rabbitmq::*;
@@ -0,0 +1,222 @@
+use std::ops::Deref;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use shuttle_service::{
+    error::{CustomError, Error},
+    resource::{ProvisionResourceRequest, ResourceType},
+    ContainerRequest, ContainerResponse, Environment, IntoResource, ResourceFactory,
+    ResourceInputBuilder,
+};
+
+/// A NATS server
+#[derive(Default)]
+pub struct Nats {
+    /// Required if deploying
+    cloud_url: Option<String>,
+    /// If given, use this instead of the default docker container on local run
+    local_url: Option<String>,
+    /// Isolates this project on a shared server. Defaults to the project name.
+    subject_prefix: Option<String>,
+}
+
+impl Nats {
+    pub fn cloud_url(mut self, cloud_url: &str) -> Self {
+        self.cloud_url = Some(cloud_url.to_string());
+        self
+    }
+    pub fn local_url(mut self, local_url: &str) -> Self {
+        self.local_url = Some(local_url.to_string());
+        self
+    }
+    pub fn subject_prefix(mut self, subject_prefix: &str) -> Self {
+        self.subject_prefix = Some(subject_prefix.to_string());
+        self
+    }
+}
+
+/// Conditionally request a Shuttle resource
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum NatsRequest {
+    Request(ProvisionResourceRequest),
+    NotRequest(NatsConfig),
+}
+
+#[async_trait]
+impl ResourceInputBuilder for Nats {
+    type Input = NatsRequest;
+    // The response can be a provisioned container, depending on local/deployment and config.
+    type Output = NatsOutput;
+
+    async fn build(self, factory: &ResourceFactory) -> Result<Self::Input, Error> {
+        let md = factory.get_metadata();
+        let subject_prefix = Some(self.subject_prefix.unwrap_or(md.project_name.clone()));
+        match md.env {
+            Environment::Deployment => match self.cloud_url {
+                Some(cloud_url) => Ok(NatsRequest::NotRequest(NatsConfig {
+                    url: cloud_url,
+                    subject_prefix,
+                })),
+                None => Err(Error::Custom(CustomError::msg(
+                    "missing `cloud_url` parameter",
+                ))),
+            },
+            Environment::Local => match self.local_url {
+                Some(local_url) => Ok(NatsRequest::NotRequest(NatsConfig {
+                    url: local_url,
+                    subject_prefix,
+                })),
+                None => Ok(NatsRequest::Request(ProvisionResourceRequest {
+                    r#type: ResourceType::Container,
+                    config: serde_json::to_value(ContainerRequest {
+                        project_name: md.project_name,
+                        container_name: "nats".to_string(),
+                        image: "docker.io/library/nats:2.10".to_string(),
+                        port: "4222/tcp".to_string(),
+                        env: vec![],
+                        cmd: None,
+                    })
+                    .unwrap(),
+                })),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum NatsOutput {
+    Container(ContainerResponse),
+    Config(NatsConfig),
+}
+
+/// Where to connect, and the subject prefix to use on that server
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NatsConfig {
+    url: String,
+    subject_prefix: Option<String>,
+}
+
+/// A NATS client along with the subject prefix of this project.
+///
+/// Derefs to [`async_nats::Client`].
+#[derive(Clone, Debug)]
+pub struct NatsClient {
+    client: async_nats::Client,
+    subject_prefix: Option<String>,
+}
+
+impl NatsClient {
+    /// Prefix a subject so that it doesn't clash with other projects on the same server
+    pub fn subject(&self, subject: &str) -> String {
+        match &self.subject_prefix {
+            Some(prefix) => format!("{prefix}.{subject}"),
+            None => subject.to_string(),
+        }
+    }
+
+    pub fn subject_prefix(&self) -> Option<&str> {
+        self.subject_prefix.as_deref()
+    }
+
+    pub fn into_inner(self) -> async_nats::Client {
+        self.client
+    }
+}
+
+impl Deref for NatsClient {
+    type Target = async_nats::Client;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl IntoResource<NatsClient> for NatsOutput {
+    async fn into_resource(self) -> Result<NatsClient, Error> {
+        let mut options = async_nats::ConnectOptions::new();
+        let config = match self {
+            // The container is dedicated to this project, so there is nothing to isolate from.
+            // It might still be starting up, so keep retrying in the background.
+            Self::Container(output) => {
+                options = options.retry_on_initial_connect();
+                NatsConfig {
+                    url: format!("nats://localhost:{}", output.host_port),
+                    subject_prefix: None,
+                }
+            }
+            Self::Config(c) => c,
+        };
+        if let Some(prefix) = &config.subject_prefix {
+            options = options.custom_inbox_prefix(format!("_INBOX.{prefix}"));
+        }
+
+        let client = options
+            .connect(config.url)
+            .await
+            .map_err(CustomError::new)?;
+
+        Ok(NatsClient {
+            client,
+            subject_prefix: config.subject_prefix,
+        })
+    }
+}
+
+#[async_trait]
+impl IntoResource<async_nats::Client> for NatsOutput {
+    async fn into_resource(self) -> Result<async_nats::Client, Error> {
+        let client: NatsClient = self.into_resource().await?;
+
+        Ok(client.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn nats_deployment() {
+        let factory = ResourceFactory::new(
+            "my-project".to_string(),
+            Default::default(),
+            Environment::Deployment,
+        );
+
+        assert!(Nats::default().build(&factory).await.is_err());
+
+        let NatsRequest::NotRequest(config) = Nats::default()
+            .cloud_url("nats://broker:4222")
+            .build(&factory)
+            .await
+            .unwrap()
+        else {
+            panic!("a cloud url should not be provisioned");
+        };
+        assert_eq!(config.url, "nats://broker:4222");
+        assert_eq!(config.subject_prefix.as_deref(), Some("my-project"));
+    }
+
+    #[tokio::test]
+    async fn nats_local() {
+        let factory = ResourceFactory::new(
+            "my-project".to_string(),
+            Default::default(),
+            Environment::Local,
+        );
+
+        let NatsRequest::Request(request) = Nats::default()
+            .cloud_url("nats://broker:4222")
+            .build(&factory)
+            .await
+            .unwrap()
+        else {
+            panic!("a container should be provisioned locally");
+        };
+        assert_eq!(request.r#type, ResourceType::Container);
+        assert_eq!(request.config["container_name"], "nats");
+    }
+}
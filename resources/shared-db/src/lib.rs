@@ -1,5 +1,7 @@
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "postgres")]
+mod migrations;
 #[cfg(feature = "postgres")]
 mod postgres;
 
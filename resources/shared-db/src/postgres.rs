@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use shuttle_service::{
-    resource::{ProvisionResourceRequest, ResourceType},
+    resource::{ProvisionResourceRequest, ResourceType, ResourceWithData},
     DatabaseResource, DbInput, Environment, Error, IntoResource, ResourceFactory,
     ResourceInputBuilder,
 };
@@ -20,9 +20,11 @@ const MIN_CONNECTIONS: u32 = 1;
 #[allow(dead_code)]
 const MAX_CONNECTIONS: u32 = 5;
 
+use crate::migrations;
+
 /// Shuttle managed Postgres DB in a shared cluster
 #[derive(Default)]
-pub struct Postgres(DbInput);
+pub struct Postgres(DbInput, Option<String>);
 
 impl Postgres {
     /// Use a custom connection string for local runs
@@ -31,14 +33,21 @@ impl Postgres {
 
         self
     }
+
+    /// Run the migrations in this directory when the resource is initialized
+    pub fn migrations(mut self, migrations: &str) -> Self {
+        self.1 = Some(migrations.to_string());
+
+        self
+    }
 }
 
-/// Conditionally request a Shuttle resource
+/// Conditionally request a Shuttle resource. Both carry the migrations directory, if any.
 #[derive(Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum MaybeRequest {
-    Request(ProvisionResourceRequest),
-    NotRequest(DatabaseResource),
+    Request(ResourceWithData<ProvisionResourceRequest, Option<String>>),
+    NotRequest(ResourceWithData<DatabaseResource, Option<String>>),
 }
 
 #[async_trait]
@@ -49,34 +58,52 @@ impl ResourceInputBuilder for Postgres {
     async fn build(self, factory: &ResourceFactory) -> Result<Self::Input, Error> {
         let md = factory.get_metadata();
         Ok(match md.env {
-            Environment::Deployment => MaybeRequest::Request(ProvisionResourceRequest {
-                r#type: ResourceType::DatabaseSharedPostgres,
-                config: serde_json::to_value(self.0).unwrap(),
-            }),
-            Environment::Local => match self.0.local_uri {
-                Some(local_uri) => {
-                    MaybeRequest::NotRequest(DatabaseResource::ConnectionString(local_uri))
-                }
-                None => MaybeRequest::Request(ProvisionResourceRequest {
+            Environment::Deployment => MaybeRequest::Request(ResourceWithData {
+                resource: ProvisionResourceRequest {
                     r#type: ResourceType::DatabaseSharedPostgres,
                     config: serde_json::to_value(self.0).unwrap(),
+                },
+                data: self.1,
+            }),
+            Environment::Local => match self.0.local_uri {
+                Some(local_uri) => MaybeRequest::NotRequest(ResourceWithData {
+                    resource: DatabaseResource::ConnectionString(local_uri),
+                    data: self.1,
+                }),
+                None => MaybeRequest::Request(ResourceWithData {
+                    resource: ProvisionResourceRequest {
+                        r#type: ResourceType::DatabaseSharedPostgres,
+                        config: serde_json::to_value(self.0).unwrap(),
+                    },
+                    data: self.1,
                 }),
             },
         })
     }
 }
 
+/// The database and the migrations directory, if any
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct OutputWrapper(DatabaseResource);
+pub struct OutputWrapper(ResourceWithData<DatabaseResource, Option<String>>);
 
+/// Runs the migrations, if any. The other resource types are built from this one.
 #[async_trait]
 impl IntoResource<String> for OutputWrapper {
     async fn into_resource(self) -> Result<String, Error> {
-        Ok(match self.0 {
+        let ResourceWithData {
+            resource,
+            data: migrations,
+        } = self.0;
+        let connection_string = match resource {
             DatabaseResource::ConnectionString(s) => s,
             DatabaseResource::Info(info) => info.connection_string(true),
-        })
+        };
+        if let Some(dir) = migrations {
+            migrations::run(&connection_string, &dir).await?;
+        }
+
+        Ok(connection_string)
     }
 }
 
@@ -0,0 +1,235 @@
+use std::time::Duration;
+
+use shuttle_common::models::{
+    resource::{ResourceState, ResourceType},
+    runtime::{RuntimeExitReason, RuntimeExitReport, RuntimePhase},
+};
+
+/// The reasons for the runtime to exit before the service stops on its own.
+///
+/// Each reason has its own exit code, and is reported with a final JSON line (see [`RuntimeExitReport`])
+/// so that the Shuttle CLI and platform can show what went wrong.
+#[derive(Debug)]
+pub enum RuntimeExit {
+    /// The service returned an error from `bind`
+    BindFailed(anyhow::Error),
+    /// A termination signal was received
+    Interrupted,
+    /// The secrets of the project could not be fetched
+    SecretsFailed(anyhow::Error),
+    /// A resource builder failed
+    LoaderFailed(anyhow::Error),
+    /// A resource builder produced an invalid resource request
+    InvalidResourceInput(anyhow::Error),
+    /// Requesting a resource failed
+    ProvisioningFailed(ResourceType, anyhow::Error),
+    /// A resource ended up in a state it does not recover from
+    ProvisioningBadState(ResourceType, ResourceState),
+    /// A resource was not ready within the provisioning timeout
+    ProvisioningTimedOut(ResourceType, Duration),
+    /// Turning the provisioned resources into the main function inputs, or the main function, failed
+    ResourceInitFailed(anyhow::Error),
+    /// Serving a health check failed
+    HealthCheckFailed(anyhow::Error),
+    /// The health check port could not be bound
+    HealthCheckBindFailed(anyhow::Error),
+    /// Accepting a health check connection failed
+    HealthCheckListenerFailed(anyhow::Error),
+}
+
+impl RuntimeExit {
+    pub fn code(&self) -> i32 {
+        match self {
+            Self::BindFailed(_) => 1,
+            Self::Interrupted => 10,
+            Self::SecretsFailed(_) => 101,
+            Self::LoaderFailed(_) => 111,
+            Self::InvalidResourceInput(_) => 121,
+            Self::ProvisioningFailed(..) => 131,
+            Self::ProvisioningBadState(..) => 132,
+            Self::ProvisioningTimedOut(..) => 133,
+            Self::ResourceInitFailed(_) => 151,
+            Self::HealthCheckFailed(_) => 200,
+            Self::HealthCheckBindFailed(_) => 201,
+            Self::HealthCheckListenerFailed(_) => 202,
+        }
+    }
+
+    pub fn reason(&self) -> RuntimeExitReason {
+        match self {
+            Self::BindFailed(_) => RuntimeExitReason::BindFailed,
+            Self::Interrupted => RuntimeExitReason::Interrupted,
+            Self::SecretsFailed(_) => RuntimeExitReason::SecretsFailed,
+            Self::LoaderFailed(_) => RuntimeExitReason::LoaderFailed,
+            Self::InvalidResourceInput(_) => RuntimeExitReason::InvalidResourceInput,
+            Self::ProvisioningFailed(..) => RuntimeExitReason::ProvisioningFailed,
+            Self::ProvisioningBadState(..) => RuntimeExitReason::ProvisioningBadState,
+            Self::ProvisioningTimedOut(..) => RuntimeExitReason::ProvisioningTimedOut,
+            Self::ResourceInitFailed(_) => RuntimeExitReason::ResourceInitFailed,
+            Self::HealthCheckFailed(_) => RuntimeExitReason::HealthCheckFailed,
+            Self::HealthCheckBindFailed(_) => RuntimeExitReason::HealthCheckBindFailed,
+            Self::HealthCheckListenerFailed(_) => RuntimeExitReason::HealthCheckListenerFailed,
+        }
+    }
+
+    pub fn phase(&self) -> RuntimePhase {
+        match self {
+            Self::BindFailed(_) | Self::Interrupted => RuntimePhase::Running,
+            Self::SecretsFailed(_) => RuntimePhase::Secrets,
+            Self::LoaderFailed(_) => RuntimePhase::Loading,
+            Self::InvalidResourceInput(_)
+            | Self::ProvisioningFailed(..)
+            | Self::ProvisioningBadState(..)
+            | Self::ProvisioningTimedOut(..) => RuntimePhase::Provisioning,
+            Self::ResourceInitFailed(_) => RuntimePhase::ResourceInit,
+            Self::HealthCheckFailed(_)
+            | Self::HealthCheckBindFailed(_)
+            | Self::HealthCheckListenerFailed(_) => RuntimePhase::HealthCheck,
+        }
+    }
+
+    pub fn resource_type(&self) -> Option<ResourceType> {
+        match self {
+            Self::ProvisioningFailed(r#type, _)
+            | Self::ProvisioningBadState(r#type, _)
+            | Self::ProvisioningTimedOut(r#type, _) => Some(*r#type),
+            _ => None,
+        }
+    }
+
+    /// The error and its causes, outermost first
+    pub fn errors(&self) -> Vec<String> {
+        match self {
+            Self::BindFailed(e)
+            | Self::SecretsFailed(e)
+            | Self::LoaderFailed(e)
+            | Self::InvalidResourceInput(e)
+            | Self::ProvisioningFailed(_, e)
+            | Self::ResourceInitFailed(e)
+            | Self::HealthCheckFailed(e)
+            | Self::HealthCheckBindFailed(e)
+            | Self::HealthCheckListenerFailed(e) => e.chain().map(|e| e.to_string()).collect(),
+            Self::Interrupted => Vec::new(),
+            Self::ProvisioningBadState(r#type, state) => {
+                vec![format!("{type} resource ended up in state '{state}'")]
+            }
+            Self::ProvisioningTimedOut(r#type, timeout) => vec![format!(
+                "{type} resource was not ready within {}s",
+                timeout.as_secs()
+            )],
+        }
+    }
+
+    pub fn report(&self) -> RuntimeExitReport {
+        RuntimeExitReport {
+            code: self.code(),
+            reason: self.reason(),
+            phase: self.phase(),
+            resource_type: self.resource_type(),
+            errors: self.errors(),
+        }
+    }
+
+    /// Print the error and the final report line, and exit the process
+    pub fn exit(self) -> ! {
+        if let Some(error) = self.errors().first() {
+            eprintln!("ERROR: Runtime {} phase failed: {error}", self.phase());
+        }
+        eprintln!("{}", self.report().to_line());
+
+        std::process::exit(self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_and_reasons() {
+        let error = || anyhow::anyhow!("oops");
+        let exits = [
+            (RuntimeExit::BindFailed(error()), 1, "bind_failed"),
+            (RuntimeExit::Interrupted, 10, "interrupted"),
+            (RuntimeExit::SecretsFailed(error()), 101, "secrets_failed"),
+            (RuntimeExit::LoaderFailed(error()), 111, "loader_failed"),
+            (
+                RuntimeExit::InvalidResourceInput(error()),
+                121,
+                "invalid_resource_input",
+            ),
+            (
+                RuntimeExit::ProvisioningFailed(ResourceType::DatabaseSharedPostgres, error()),
+                131,
+                "provisioning_failed",
+            ),
+            (
+                RuntimeExit::ProvisioningBadState(
+                    ResourceType::DatabaseSharedPostgres,
+                    ResourceState::Failed,
+                ),
+                132,
+                "provisioning_bad_state",
+            ),
+            (
+                RuntimeExit::ProvisioningTimedOut(
+                    ResourceType::DatabaseSharedPostgres,
+                    Duration::from_secs(900),
+                ),
+                133,
+                "provisioning_timed_out",
+            ),
+            (
+                RuntimeExit::ResourceInitFailed(error()),
+                151,
+                "resource_init_failed",
+            ),
+            (
+                RuntimeExit::HealthCheckFailed(error()),
+                200,
+                "health_check_failed",
+            ),
+            (
+                RuntimeExit::HealthCheckBindFailed(error()),
+                201,
+                "health_check_bind_failed",
+            ),
+            (
+                RuntimeExit::HealthCheckListenerFailed(error()),
+                202,
+                "health_check_listener_failed",
+            ),
+        ];
+
+        for (exit, code, reason) in exits {
+            let report = exit.report();
+            assert_eq!(report.code, code, "{reason}");
+            assert_eq!(report.reason.to_string(), reason);
+            assert_eq!(report.phase, exit.phase());
+            // the reason is the same in the JSON line
+            assert!(report
+                .to_line()
+                .contains(&format!(r#""reason":"{reason}""#)));
+        }
+    }
+
+    #[test]
+    fn exit_report_contents() {
+        let report = RuntimeExit::ProvisioningTimedOut(
+            ResourceType::DatabaseSharedPostgres,
+            Duration::from_secs(900),
+        )
+        .report();
+        assert_eq!(report.phase, RuntimePhase::Provisioning);
+        assert_eq!(
+            report.resource_type,
+            Some(ResourceType::DatabaseSharedPostgres)
+        );
+        assert_eq!(report.errors.len(), 1);
+
+        let report = RuntimeExit::LoaderFailed(anyhow::anyhow!("inner").context("outer")).report();
+        assert_eq!(report.phase, RuntimePhase::Loading);
+        assert_eq!(report.resource_type, None);
+        assert_eq!(report.errors, ["outer", "inner"]);
+    }
+}
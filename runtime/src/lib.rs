@@ -4,6 +4,8 @@
     html_favicon_url = "https://raw.githubusercontent.com/shuttle-hq/shuttle/main/assets/favicon.ico"
 )]
 
+/// Runtime exit reasons
+mod exit;
 /// Built-in plugins
 mod plugins;
 /// shuttle.dev runtime
@@ -16,11 +18,12 @@ mod telemetry;
 // Public API
 // Useful re-exports
 pub use async_trait::async_trait;
+pub use exit::RuntimeExit;
 pub use plugins::{Metadata, Secrets};
 pub use shuttle_codegen::main;
 pub use shuttle_service::{
-    CustomError, DbInput, DeploymentMetadata, Environment, Error, IntoResource, ResourceFactory,
-    ResourceInputBuilder, SecretStore, Service,
+    CustomError, DbInput, DeploymentMetadata, Environment, Error, HealthCheckKind, HealthChecks,
+    IntoResource, ResourceFactory, ResourceInputBuilder, SecretStore, Service, Shutdown,
 };
 pub use tokio;
 
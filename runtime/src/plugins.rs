@@ -57,6 +57,27 @@ impl ResourceInputBuilder for Metadata {
 ///     Ok(router.into())
 /// }
 /// ```
+///
+/// ### Typed secrets
+///
+/// The parameter can also be any type implementing `serde::Deserialize`, which the secrets are
+/// deserialized into with [`SecretStore::deserialize`]. Nested structs are read from TOML tables
+/// (or dotted keys), and the service fails to start with the name of any missing or invalid secret.
+///
+/// ```rust,ignore
+/// #[derive(serde::Deserialize)]
+/// struct MyConfig {
+///     api_key: String,
+///     database: DatabaseConfig, // from a `[database]` table
+/// }
+///
+/// #[shuttle_runtime::main]
+/// async fn main(
+///     #[shuttle_runtime::Secrets] config: MyConfig
+/// ) -> ShuttleAxum {
+///     // ...
+/// }
+/// ```
 #[derive(Default)]
 pub struct Secrets;
 
@@ -2,23 +2,49 @@ use std::{
     collections::BTreeMap,
     iter::FromIterator,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    process::exit,
+    time::Duration,
 };
 
 use anyhow::Context;
-use http_body_util::Empty;
+use http_body_util::Full;
 use hyper::{body::Bytes, server::conn::http1, service::service_fn, Response};
 use hyper_util::rt::TokioIo;
 use shuttle_api_client::ShuttleApiClient;
 use shuttle_common::{
-    models::resource::{ResourceInput, ResourceState, ResourceType},
+    models::{
+        error::ApiError,
+        resource::{
+            ProvisionResourceRequest, ResourceInput, ResourceState, ResourceType, ResourceWithData,
+        },
+    },
     secrets::Secret,
 };
-use shuttle_service::{Environment, ResourceFactory, Service};
-use tokio::net::TcpListener;
+use shuttle_service::{
+    Environment, HealthCheckKind, HealthChecks, ResourceFactory, Service, Shutdown,
+};
+use tokio::net::{TcpListener, TcpStream};
 use tracing::{debug, info, trace};
 
-use crate::__internals::{Loader, Runner};
+use crate::{
+    __internals::{Loader, Runner},
+    RuntimeExit,
+};
+
+/// How long a resource may take to provision, unless overridden
+const DEFAULT_PROVISION_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Creating a dedicated RDS instance takes a lot longer than the other resources
+const RDS_PROVISION_TIMEOUT: Duration = Duration::from_secs(45 * 60);
+/// Bounds for the exponential backoff between provisioning requests
+const PROVISION_MIN_INTERVAL: Duration = Duration::from_secs(1);
+const PROVISION_MAX_INTERVAL: Duration = Duration::from_secs(16);
+/// How many provisioning requests in a row may fail with a transient error before giving up
+const PROVISION_MAX_RETRIES: u32 = 8;
+
+/// How long the application health checks get to respond
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long the service gets to finish in-flight work after a termination signal, unless overridden
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
 
 struct RuntimeEnvVars {
     /// Are we running in a Shuttle deployment?
@@ -36,6 +62,10 @@ struct RuntimeEnvVars {
     api_url: String,
     /// Key for the API calls (if relevant)
     api_key: Option<String>,
+    /// How long to wait for the service to shut down gracefully
+    shutdown_timeout: Duration,
+    /// How long to wait for each resource to be provisioned, instead of the default for its type
+    provision_timeout: Option<Duration>,
 }
 
 impl RuntimeEnvVars {
@@ -64,7 +94,142 @@ impl RuntimeEnvVars {
                 .ok(),
             api_url: std::env::var("SHUTTLE_API").expect("api url env var"),
             api_key: std::env::var("SHUTTLE_API_KEY").ok(),
+            shutdown_timeout: std::env::var("SHUTTLE_SHUTDOWN_TIMEOUT")
+                .map(|s| Duration::from_secs(s.parse().expect("invalid shutdown timeout")))
+                .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT),
+            provision_timeout: std::env::var("SHUTTLE_PROVISION_TIMEOUT")
+                .map(|s| Duration::from_secs(s.parse().expect("invalid provision timeout")))
+                .ok(),
+        }
+    }
+}
+
+/// Run the application health checks for the probe at `path`.
+/// `/healthz/ready` runs all checks, other paths (such as `/healthz/live`) run the liveness checks.
+async fn health_check_response(path: &str) -> Response<Full<Bytes>> {
+    let probe = match path.trim_end_matches('/') {
+        "/healthz/ready" | "/ready" => HealthCheckKind::Readiness,
+        _ => HealthCheckKind::Liveness,
+    };
+
+    let body = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, HealthChecks::global().check(probe))
+        .await
+    {
+        Ok(report) => {
+            if !report.healthy {
+                debug!(?probe, failing = ?report.failing, "Health check failed");
+            }
+            serde_json::json!(report)
+        }
+        Err(_) => serde_json::json!({
+            "healthy": false,
+            "failing": [{ "name": "timeout", "kind": probe, "error": "health checks timed out" }],
+        }),
+    };
+
+    let status = if body["healthy"] == true {
+        hyper::StatusCode::OK
+    } else {
+        hyper::StatusCode::SERVICE_UNAVAILABLE
+    };
+    Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .expect("health check response to be valid")
+}
+
+/// Whether a failed API request is worth retrying: failed connections, timeouts, rate limits and server errors
+fn is_transient(error: &anyhow::Error) -> bool {
+    error.chain().any(|e| {
+        if let Some(api_error) = e.downcast_ref::<ApiError>() {
+            return matches!(api_error.status_code, 408 | 429 | 500..=599);
+        }
+        let reqwest_error = match e.downcast_ref::<reqwest_middleware::Error>() {
+            Some(reqwest_middleware::Error::Reqwest(e)) => Some(e),
+            _ => e.downcast_ref::<reqwest::Error>(),
+        };
+
+        reqwest_error.is_some_and(|e| e.is_connect() || e.is_timeout())
+    })
+}
+
+/// How long a resource of this type may take to provision
+fn default_provision_timeout(r#type: ResourceType) -> Duration {
+    match r#type {
+        ResourceType::DatabaseAwsRdsPostgres
+        | ResourceType::DatabaseAwsRdsMySql
+        | ResourceType::DatabaseAwsRdsMariaDB => RDS_PROVISION_TIMEOUT,
+        _ => DEFAULT_PROVISION_TIMEOUT,
+    }
+}
+
+/// The wait after `interval` in the exponential backoff between provisioning requests
+fn next_backoff(interval: Duration) -> Duration {
+    (interval * 2).min(PROVISION_MAX_INTERVAL)
+}
+
+/// Request a resource until it is ready, polling with an exponential backoff.
+/// Transient request errors are retried with the same backoff.
+async fn provision(
+    client: &ShuttleApiClient,
+    project_id: &str,
+    resource: ProvisionResourceRequest,
+    timeout: Duration,
+) -> Result<serde_json::Value, RuntimeExit> {
+    let r#type = resource.r#type;
+    let started = tokio::time::Instant::now();
+    let deadline = started + timeout;
+    let mut interval = PROVISION_MIN_INTERVAL;
+    let mut retries = 0;
+
+    info!("Provisioning {:?}", r#type);
+    loop {
+        trace!("Checking state of {:?}", r#type);
+        match client
+            .provision_resource(project_id, resource.clone())
+            .await
+        {
+            Ok(res) => {
+                trace!("Got response {:?}", res);
+                retries = 0;
+                match res.state {
+                    ResourceState::Provisioning | ResourceState::Authorizing => {
+                        info!(
+                            "{:?} is {} ({}s elapsed)",
+                            r#type,
+                            res.state,
+                            started.elapsed().as_secs()
+                        );
+                    }
+                    ResourceState::Ready => {
+                        info!(
+                            "{:?} is ready ({}s elapsed)",
+                            r#type,
+                            started.elapsed().as_secs()
+                        );
+                        return Ok(res.output);
+                    }
+                    bad_state => return Err(RuntimeExit::ProvisioningBadState(r#type, bad_state)),
+                }
+            }
+            Err(e) if is_transient(&e) && retries < PROVISION_MAX_RETRIES => {
+                retries += 1;
+                tracing::warn!(
+                    "Provisioning {:?} failed, retrying ({}/{}): {e:#}",
+                    r#type,
+                    retries,
+                    PROVISION_MAX_RETRIES
+                );
+            }
+            Err(e) => return Err(RuntimeExit::ProvisioningFailed(r#type, e)),
         }
+
+        if tokio::time::Instant::now() + interval > deadline {
+            return Err(RuntimeExit::ProvisioningTimedOut(r#type, timeout));
+        }
+        tokio::time::sleep(interval).await;
+        interval = next_backoff(interval);
     }
 }
 
@@ -81,6 +246,8 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
         healthz_port,
         api_url,
         api_key,
+        shutdown_timeout,
+        provision_timeout,
     } = RuntimeEnvVars::parse();
 
     let service_addr = SocketAddr::new(ip, port);
@@ -92,15 +259,21 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
         let addr = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), healthz_port);
         tokio::spawn(async move {
             // light hyper server
-            let Ok(listener) = TcpListener::bind(&addr).await else {
-                eprintln!("ERROR: Failed to bind to health check port");
-                exit(201);
+            let listener = match TcpListener::bind(&addr).await {
+                Ok(listener) => listener,
+                Err(e) => RuntimeExit::HealthCheckBindFailed(
+                    anyhow::Error::new(e).context("failed to bind to health check port"),
+                )
+                .exit(),
             };
 
             loop {
-                let Ok((stream, _)) = listener.accept().await else {
-                    eprintln!("ERROR: Health check listener error");
-                    exit(202);
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => RuntimeExit::HealthCheckListenerFailed(
+                        anyhow::Error::new(e).context("health check listener error"),
+                    )
+                    .exit(),
                 };
                 let io = TokioIo::new(stream);
 
@@ -108,19 +281,16 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
                     if let Err(err) = http1::Builder::new()
                         .serve_connection(
                             io,
-                            service_fn(|_req| async move {
+                            service_fn(|req| async move {
                                 trace!("Received health check");
-                                // TODO: A hook into the `Service` trait can be added here
+                                let response = health_check_response(req.uri().path()).await;
                                 trace!("Responding to health check");
-                                Result::<Response<Empty<Bytes>>, hyper::Error>::Ok(Response::new(
-                                    Empty::new(),
-                                ))
+                                Result::<Response<Full<Bytes>>, hyper::Error>::Ok(response)
                             }),
                         )
                         .await
                     {
-                        eprintln!("ERROR: Health check error: {err}");
-                        exit(200);
+                        RuntimeExit::HealthCheckFailed(err.into()).exit();
                     }
                 });
             }
@@ -139,10 +309,7 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
         .and_then(|r| serde_json::from_value(r.output).context("failed to deserialize secrets"))
     {
         Ok(s) => s,
-        Err(e) => {
-            eprintln!("ERROR: Runtime Secret Loading phase failed: {e}");
-            exit(101);
-        }
+        Err(e) => RuntimeExit::SecretsFailed(e).exit(),
     };
 
     // Sort secrets by key
@@ -152,10 +319,7 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
     let factory = ResourceFactory::new(project_name, secrets.clone(), env);
     let mut resources = match loader.load(factory).await {
         Ok(r) => r,
-        Err(e) => {
-            eprintln!("ERROR: Runtime Loader phase failed: {e}");
-            exit(111);
-        }
+        Err(e) => RuntimeExit::LoaderFailed(e.into()).exit(),
     };
 
     // Fail early if any byte vec is invalid json
@@ -167,63 +331,57 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
         .collect::<anyhow::Result<Vec<_>>>()
     {
         Ok(v) => v,
-        Err(e) => {
-            eprintln!("ERROR: Runtime Provisioning phase failed: {e}");
-            exit(121);
-        }
+        Err(e) => RuntimeExit::InvalidResourceInput(e).exit(),
     };
 
-    for (bytes, shuttle_resource) in resources
-        .iter_mut()
-        .zip(values)
+    // Provision the Shuttle resources concurrently, and put each output in place of its input
+    let mut provisioning = tokio::task::JoinSet::new();
+    for (index, shuttle_resource, data) in values
+        .into_iter()
+        .enumerate()
         // ignore non-Shuttle resource items
-        .filter_map(|(bytes, value)| match value {
-            ResourceInput::Shuttle(shuttle_resource) => Some((bytes, shuttle_resource)),
+        .filter_map(|(index, value)| match value {
+            ResourceInput::Shuttle(shuttle_resource) => Some((index, shuttle_resource, None)),
+            ResourceInput::ShuttleWithData(ResourceWithData { resource, data }) => {
+                Some((index, resource, Some(data)))
+            }
             ResourceInput::Custom(_) => None,
         })
     {
         // Secrets don't need to be requested here since we already got them above.
         if shuttle_resource.r#type == ResourceType::Secrets {
-            *bytes = serde_json::to_vec(&secrets).expect("to serialize struct");
+            resources[index] = serde_json::to_vec(&secrets).expect("to serialize struct");
             continue;
         }
 
-        info!("Provisioning {:?}", shuttle_resource.r#type);
-        loop {
-            trace!("Checking state of {:?}", shuttle_resource.r#type);
-            match client
-                .provision_resource(&project_id, shuttle_resource.clone())
+        let client = client.clone();
+        let project_id = project_id.clone();
+        let timeout =
+            provision_timeout.unwrap_or_else(|| default_provision_timeout(shuttle_resource.r#type));
+        provisioning.spawn(async move {
+            let output = provision(&client, &project_id, shuttle_resource, timeout)
                 .await
-            {
-                Ok(res) => {
-                    trace!("Got response {:?}", res);
-                    match res.state {
-                        ResourceState::Provisioning | ResourceState::Authorizing => {
-                            tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
-                        }
-                        ResourceState::Ready => {
-                            *bytes = serde_json::to_vec(&res.output).expect("to serialize struct");
-                            break;
-                        }
-                        bad_state => {
-                            eprintln!(
-                                "ERROR: Runtime Provisioning phase failed: Received {:?} resource with state '{}'.",
-                                shuttle_resource.r#type,
-                                bad_state
-                            );
-                            exit(132);
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("ERROR: Runtime Provisioning phase failed: {e}");
-                    exit(131);
-                }
-            };
-        }
+                // hand the data back to the resource along with its output
+                .map(|output| match data {
+                    Some(data) => serde_json::json!(ResourceWithData {
+                        resource: output,
+                        data
+                    }),
+                    None => output,
+                });
+            (index, output)
+        });
     }
 
-    // TODO?: call API to say running state is being entered
+    while let Some(joined) = provisioning.join_next().await {
+        let (index, output) = joined.expect("provisioning task to not panic");
+        match output {
+            Ok(output) => {
+                resources[index] = serde_json::to_vec(&output).expect("to serialize struct")
+            }
+            Err(exit) => exit.exit(),
+        }
+    }
 
     if shuttle {
         trace!("Sending sidecar shutdown request");
@@ -238,10 +396,7 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
 
     let service = match runner.run(resources).await {
         Ok(s) => s,
-        Err(e) => {
-            eprintln!("ERROR: Runtime Resource Initialization phase failed: {e}");
-            exit(151);
-        }
+        Err(e) => RuntimeExit::ResourceInitFailed(e.into()).exit(),
     };
 
     //
@@ -249,7 +404,32 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
     //
     info!("Starting service");
 
-    let service_bind = service.bind(service_addr);
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let shutdown = Shutdown::new(
+        async move {
+            let _ = shutdown_rx.await;
+        },
+        shutdown_timeout,
+    );
+    let mut service_bind = service.bind_with_shutdown(service_addr, shutdown);
+
+    // Services whose framework wrapper does not signal readiness are ready once their port accepts connections
+    tokio::spawn(async move {
+        let probe_addr = if ip.is_unspecified() {
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port)
+        } else {
+            service_addr
+        };
+        let health = HealthChecks::global();
+        while !health.is_ready() {
+            if TcpStream::connect(probe_addr).await.is_ok() {
+                health.set_ready();
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        info!("Service is ready");
+    });
 
     #[cfg(target_family = "unix")]
     let interrupted = {
@@ -260,10 +440,10 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
             tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt())
                 .expect("Can not get the SIGINT signal receptor");
         tokio::select! {
-            res = service_bind => {
+            res = &mut service_bind => {
                 if let Err(e) = res {
                     tracing::error!("Service encountered an error in `bind`: {e}");
-                    exit(1);
+                    RuntimeExit::BindFailed(e.into()).exit();
                 }
                 tracing::warn!("Service terminated on its own. Shutting down the runtime...");
                 false
@@ -291,10 +471,10 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
         let mut ctrl_shutdown_notif = tokio::signal::windows::ctrl_shutdown()
             .expect("Can not get the CtrlShutdown signal receptor");
         tokio::select! {
-            res = service_bind => {
+            res = &mut service_bind => {
                 if let Err(e) = res {
                     tracing::error!("Service encountered an error in `bind`: {e}");
-                    exit(1);
+                    RuntimeExit::BindFailed(e.into()).exit();
                 }
                 tracing::warn!("Service terminated on its own. Shutting down the runtime...");
                 false
@@ -323,6 +503,72 @@ pub async fn start(loader: impl Loader + Send + 'static, runner: impl Runner + S
     };
 
     if interrupted {
-        exit(10);
+        info!(
+            "Waiting up to {}s for the service to shut down",
+            shutdown_timeout.as_secs()
+        );
+        let _ = shutdown_tx.send(());
+        match tokio::time::timeout(shutdown_timeout, service_bind).await {
+            Ok(Ok(())) => info!("Service shut down gracefully"),
+            Ok(Err(e)) => tracing::error!("Service encountered an error while shutting down: {e}"),
+            Err(_) => tracing::warn!("Service did not shut down within the timeout"),
+        }
+        RuntimeExit::Interrupted.exit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_error(status_code: u16) -> anyhow::Error {
+        ApiError {
+            message: "oops".to_owned(),
+            status_code,
+        }
+        .into()
+    }
+
+    #[tokio::test]
+    async fn transient_errors() {
+        for status_code in [408, 429, 500, 502, 503] {
+            assert!(is_transient(&api_error(status_code)), "{status_code}");
+        }
+        for status_code in [400, 401, 403, 404, 409, 422] {
+            assert!(!is_transient(&api_error(status_code)), "{status_code}");
+        }
+        assert!(is_transient(
+            &api_error(503).context("failed to provision resource")
+        ));
+        assert!(!is_transient(&anyhow::anyhow!(
+            "failed to parse a successful response"
+        )));
+
+        // nothing listens on port 1
+        let connect_error = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+        assert!(is_transient(&connect_error.into()));
+    }
+
+    #[test]
+    fn backoff_schedule() {
+        let schedule =
+            std::iter::successors(Some(PROVISION_MIN_INTERVAL), |i| Some(next_backoff(*i)))
+                .take(7)
+                .map(|i| i.as_secs())
+                .collect::<Vec<_>>();
+
+        assert_eq!(schedule, [1, 2, 4, 8, 16, 16, 16]);
+    }
+
+    #[test]
+    fn rds_gets_longer_provision_timeout() {
+        assert_eq!(
+            default_provision_timeout(ResourceType::DatabaseAwsRdsPostgres),
+            RDS_PROVISION_TIMEOUT
+        );
+        assert_eq!(
+            default_provision_timeout(ResourceType::DatabaseSharedPostgres),
+            DEFAULT_PROVISION_TIMEOUT
+        );
     }
 }
@@ -0,0 +1,220 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex, OnceLock,
+};
+
+use serde::Serialize;
+
+type CheckFn = dyn Fn() -> Pin<Box<dyn Future<Output = Result<(), String>> + Send>> + Send + Sync;
+
+/// Which probe a health check is part of
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthCheckKind {
+    /// Failing liveness checks mean the service is broken and should be restarted
+    Liveness,
+    /// Failing readiness checks mean the service should not receive traffic for now,
+    /// for example when a connection pool is exhausted
+    Readiness,
+}
+
+struct HealthCheck {
+    name: String,
+    kind: HealthCheckKind,
+    check: Arc<CheckFn>,
+}
+
+/// Registry of application-defined health checks, served on the health check port of the runtime.
+///
+/// The liveness probe runs the liveness checks, and the readiness probe runs all checks.
+/// The readiness probe also fails until the service is marked as ready with [`HealthChecks::set_ready`].
+///
+/// ```rust,ignore
+/// #[shuttle_runtime::main]
+/// async fn main(#[shuttle_shared_db::Postgres] pool: PgPool) -> ShuttleAxum {
+///     let db = pool.clone();
+///     HealthChecks::global().readiness("database", move || {
+///         let db = db.clone();
+///         async move {
+///             if db.num_idle() == 0 && db.size() == db.options().get_max_connections() {
+///                 return Err("connection pool exhausted");
+///             }
+///             Ok(())
+///         }
+///     });
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct HealthChecks {
+    checks: Arc<Mutex<Vec<HealthCheck>>>,
+    ready: Arc<AtomicBool>,
+}
+
+impl HealthChecks {
+    /// The registry used by the Shuttle runtime
+    pub fn global() -> &'static HealthChecks {
+        static GLOBAL: OnceLock<HealthChecks> = OnceLock::new();
+        GLOBAL.get_or_init(HealthChecks::default)
+    }
+
+    /// Mark the service as ready to take traffic.
+    ///
+    /// Framework wrappers call this on the global registry once their socket is bound.
+    /// Services that don't do so are marked ready by the runtime once their port accepts connections.
+    pub fn set_ready(&self) {
+        self.ready.store(true, Ordering::Release);
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Register a check that reports whether the service is alive
+    pub fn liveness<F, Fut, E>(&self, name: impl Into<String>, check: F) -> &Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.register(name.into(), HealthCheckKind::Liveness, check)
+    }
+
+    /// Register a check that reports whether the service can take traffic
+    pub fn readiness<F, Fut, E>(&self, name: impl Into<String>, check: F) -> &Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.register(name.into(), HealthCheckKind::Readiness, check)
+    }
+
+    fn register<F, Fut, E>(&self, name: String, kind: HealthCheckKind, check: F) -> &Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        let check: Arc<CheckFn> = Arc::new(move || {
+            let fut = check();
+            Box::pin(async move { fut.await.map_err(|e| e.to_string()) })
+        });
+        self.checks
+            .lock()
+            .expect("health checks lock to not be poisoned")
+            .push(HealthCheck { name, kind, check });
+
+        self
+    }
+
+    /// Run the checks for a probe. Readiness includes the liveness checks.
+    pub async fn check(&self, probe: HealthCheckKind) -> HealthReport {
+        let checks = self
+            .checks
+            .lock()
+            .expect("health checks lock to not be poisoned")
+            .iter()
+            .filter(|c| probe == HealthCheckKind::Readiness || c.kind == probe)
+            .map(|c| (c.name.clone(), c.kind, c.check.clone()))
+            .collect::<Vec<_>>();
+
+        let mut report = HealthReport {
+            healthy: true,
+            failing: Vec::new(),
+        };
+        if probe == HealthCheckKind::Readiness && !self.is_ready() {
+            report.healthy = false;
+            report.failing.push(FailingHealthCheck {
+                name: "listener".to_owned(),
+                kind: HealthCheckKind::Readiness,
+                error: "service is not listening yet".to_owned(),
+            });
+        }
+        for (name, kind, check) in checks {
+            if let Err(error) = check().await {
+                report.healthy = false;
+                report
+                    .failing
+                    .push(FailingHealthCheck { name, kind, error });
+            }
+        }
+
+        report
+    }
+}
+
+/// Result of running the health checks for a probe
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub failing: Vec<FailingHealthCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailingHealthCheck {
+    pub name: String,
+    pub kind: HealthCheckKind,
+    pub error: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failing_names(report: &HealthReport) -> Vec<&str> {
+        report.failing.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn readiness_waits_for_listener() {
+        let checks = HealthChecks::default();
+        assert!(!checks.is_ready());
+
+        let report = checks.check(HealthCheckKind::Readiness).await;
+        assert!(!report.healthy);
+        assert_eq!(failing_names(&report), ["listener"]);
+        // liveness does not depend on the listener
+        assert!(checks.check(HealthCheckKind::Liveness).await.healthy);
+
+        checks.set_ready();
+        assert!(checks.is_ready());
+        assert!(checks.check(HealthCheckKind::Readiness).await.healthy);
+    }
+
+    #[tokio::test]
+    async fn probes_run_their_checks() {
+        let checks = HealthChecks::default();
+        checks.set_ready();
+        checks
+            .liveness("deadlock", || async { Err("worker stuck") })
+            .readiness("database", || async { Err("pool exhausted") })
+            .readiness("cache", || async { Ok::<_, String>(()) });
+
+        // liveness only runs the liveness checks
+        let report = checks.check(HealthCheckKind::Liveness).await;
+        assert!(!report.healthy);
+        assert_eq!(failing_names(&report), ["deadlock"]);
+
+        // readiness runs all checks
+        let report = checks.check(HealthCheckKind::Readiness).await;
+        assert!(!report.healthy);
+        assert_eq!(failing_names(&report), ["deadlock", "database"]);
+        assert_eq!(report.failing[1].kind, HealthCheckKind::Readiness);
+        assert_eq!(report.failing[1].error, "pool exhausted");
+    }
+
+    #[tokio::test]
+    async fn clones_share_registrations() {
+        let checks = HealthChecks::default();
+        let clone = checks.clone();
+        clone.liveness("always", || async { Ok::<_, String>(()) });
+        clone.set_ready();
+
+        assert!(checks.is_ready());
+        assert_eq!(checks.checks.lock().unwrap().len(), 1);
+    }
+}
@@ -1,6 +1,10 @@
 use std::collections::BTreeMap;
+use std::future::Future;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use serde::{de::DeserializeOwned, Serialize};
@@ -11,12 +15,15 @@ pub use shuttle_common::{
         resource,
     },
     secrets::{Secret, SecretStore},
-    ContainerRequest, ContainerResponse, DatabaseInfo, DatabaseResource, DbInput,
+    BucketInput, ContainerRequest, ContainerResponse, DatabaseInfo, DatabaseResource, DbInput,
+    S3Config,
 };
 
 pub use crate::error::{CustomError, Error};
+pub use crate::health::{HealthCheckKind, HealthChecks, HealthReport};
 
 pub mod error;
+pub mod health;
 
 /// Allows implementing plugins for the Shuttle main function.
 ///
@@ -38,6 +45,8 @@ pub trait ResourceInputBuilder: Default {
     /// If the input is a [`shuttle_common::resource::ProvisionResourceRequest`],
     /// then the resource will be provisioned and the associated output type will
     /// be put in [`ResourceInputBuilder::Output`].
+    /// A request wrapped in a [`shuttle_common::resource::ResourceWithData`] gets its output
+    /// wrapped with the same data.
     type Input: Serialize + DeserializeOwned;
 
     /// The output from provisioning this resource.
@@ -76,6 +85,11 @@ impl ResourceFactory {
         self.secrets.clone()
     }
 
+    /// Health checks served by the runtime, for plugins that want to report on their resource
+    pub fn health_checks(&self) -> &'static HealthChecks {
+        HealthChecks::global()
+    }
+
     pub fn get_metadata(&self) -> DeploymentMetadata {
         DeploymentMetadata {
             env: self.env,
@@ -114,4 +128,60 @@ pub trait Service: Send {
     /// The passed [`SocketAddr`] receives proxied HTTP traffic from your Shuttle subdomain (or custom domain).
     /// Binding to the address is only relevant if this service is an HTTP server.
     async fn bind(mut self, addr: SocketAddr) -> Result<(), error::Error>;
+
+    /// Like [`Service::bind`], but should stop accepting new work and return once in-flight work is done
+    /// when `shutdown` resolves. The runtime waits up to [`Shutdown::timeout`] for this to return
+    /// before exiting.
+    ///
+    /// The default implementation drops the [`Service::bind`] future when `shutdown` resolves,
+    /// which cuts any in-flight work.
+    async fn bind_with_shutdown(
+        self,
+        addr: SocketAddr,
+        shutdown: Shutdown,
+    ) -> Result<(), error::Error>
+    where
+        Self: Sized,
+    {
+        let mut bind = self.bind(addr);
+        let mut shutdown = shutdown;
+
+        std::future::poll_fn(|cx| {
+            if let Poll::Ready(res) = bind.as_mut().poll(cx) {
+                return Poll::Ready(res);
+            }
+            Pin::new(&mut shutdown).poll(cx).map(Ok)
+        })
+        .await
+    }
+}
+
+/// A signal for a [`Service`] to shut down gracefully, see [`Service::bind_with_shutdown`].
+///
+/// Resolves when the runtime receives a termination signal.
+pub struct Shutdown {
+    signal: Pin<Box<dyn Future<Output = ()> + Send>>,
+    timeout: Duration,
+}
+
+impl Shutdown {
+    pub fn new(signal: impl Future<Output = ()> + Send + 'static, timeout: Duration) -> Self {
+        Self {
+            signal: Box::pin(signal),
+            timeout,
+        }
+    }
+
+    /// How long in-flight work may take to finish after the signal, before the runtime exits
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl Future for Shutdown {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.signal.as_mut().poll(cx)
+    }
 }
@@ -27,6 +27,35 @@ where
 
         Ok(())
     }
+
+    /// Stops accepting connections on shutdown and lets workers finish in-flight requests
+    /// for up to [`shuttle_runtime::Shutdown::timeout`].
+    async fn bind_with_shutdown(
+        self,
+        addr: SocketAddr,
+        shutdown: shuttle_runtime::Shutdown,
+    ) -> Result<(), shuttle_runtime::Error> {
+        let worker_count = num_cpus::get().min(4);
+
+        let server =
+            actix_web::HttpServer::new(move || actix_web::App::new().configure(self.0.clone()))
+                .workers(worker_count)
+                .disable_signals()
+                .shutdown_timeout(shutdown.timeout().as_secs())
+                .bind(addr)?
+                .run();
+        shuttle_runtime::HealthChecks::global().set_ready();
+
+        let handle = server.handle();
+        shuttle_runtime::tokio::spawn(async move {
+            shutdown.await;
+            handle.stop(true).await;
+        });
+
+        server.await.map_err(shuttle_runtime::CustomError::new)?;
+
+        Ok(())
+    }
 }
 
 impl<F> From<F> for ActixWebService<F>
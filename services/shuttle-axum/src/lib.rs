@@ -1,5 +1,5 @@
 #![doc = include_str!("../README.md")]
-use shuttle_runtime::{CustomError, Error};
+use shuttle_runtime::{CustomError, Error, HealthChecks, Shutdown};
 use std::net::SocketAddr;
 
 #[cfg(feature = "axum")]
@@ -41,6 +41,27 @@ impl shuttle_runtime::Service for AxumService {
 
         Ok(())
     }
+
+    /// Stops accepting connections on shutdown and waits for in-flight requests to finish.
+    async fn bind_with_shutdown(self, addr: SocketAddr, shutdown: Shutdown) -> Result<(), Error> {
+        let listener = shuttle_runtime::tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(CustomError::new)?;
+        HealthChecks::global().set_ready();
+
+        #[cfg(feature = "axum")]
+        axum::serve(listener, self.0)
+            .with_graceful_shutdown(shutdown)
+            .await
+            .map_err(CustomError::new)?;
+        #[cfg(feature = "axum-0-7")]
+        axum_0_7::serve(listener, self.0)
+            .with_graceful_shutdown(shutdown)
+            .await
+            .map_err(CustomError::new)?;
+
+        Ok(())
+    }
 }
 
 impl From<Router> for AxumService {
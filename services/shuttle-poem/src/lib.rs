@@ -18,6 +18,30 @@ where
 
         Ok(())
     }
+
+    /// Stops accepting connections on shutdown and waits for in-flight requests to finish
+    /// for up to [`shuttle_runtime::Shutdown::timeout`].
+    async fn bind_with_shutdown(
+        self,
+        addr: std::net::SocketAddr,
+        shutdown: shuttle_runtime::Shutdown,
+    ) -> Result<(), shuttle_runtime::Error> {
+        use poem::listener::Listener;
+
+        let acceptor = poem::listener::TcpListener::bind(addr)
+            .into_acceptor()
+            .await
+            .map_err(shuttle_runtime::CustomError::new)?;
+        shuttle_runtime::HealthChecks::global().set_ready();
+
+        let timeout = shutdown.timeout();
+        poem::Server::new_with_acceptor(acceptor)
+            .run_with_graceful_shutdown(self.0, shutdown, Some(timeout))
+            .await
+            .map_err(shuttle_runtime::CustomError::new)?;
+
+        Ok(())
+    }
 }
 
 impl<T> From<T> for PoemService<T>
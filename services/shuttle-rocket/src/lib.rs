@@ -16,6 +16,60 @@ impl shuttle_runtime::Service for RocketService {
             ..rocket::config::Shutdown::default()
         };
 
+        let _rocket = self
+            .configure(addr, shutdown)
+            .launch()
+            .await
+            .map_err(shuttle_runtime::CustomError::new)?;
+
+        Ok(())
+    }
+
+    /// Notifies Rocket on shutdown, which stops accepting connections and gives in-flight requests
+    /// the grace period of [`shuttle_runtime::Shutdown::timeout`].
+    async fn bind_with_shutdown(
+        self,
+        addr: SocketAddr,
+        shutdown: shuttle_runtime::Shutdown,
+    ) -> Result<(), shuttle_runtime::Error> {
+        let config = rocket::config::Shutdown {
+            ctrlc: false,
+            grace: shutdown.timeout().as_secs() as u32,
+            mercy: 0,
+            ..rocket::config::Shutdown::default()
+        };
+
+        let rocket = self
+            .configure(addr, config)
+            .attach(rocket::fairing::AdHoc::on_liftoff(
+                "Shuttle readiness",
+                |_| Box::pin(async { shuttle_runtime::HealthChecks::global().set_ready() }),
+            ))
+            .ignite()
+            .await
+            .map_err(shuttle_runtime::CustomError::new)?;
+
+        let handle = rocket.shutdown();
+        shuttle_runtime::tokio::spawn(async move {
+            shutdown.await;
+            handle.notify();
+        });
+
+        let _rocket = rocket
+            .launch()
+            .await
+            .map_err(shuttle_runtime::CustomError::new)?;
+
+        Ok(())
+    }
+}
+
+impl RocketService {
+    fn configure(
+        self,
+        addr: SocketAddr,
+        shutdown: rocket::config::Shutdown,
+    ) -> rocket::Rocket<rocket::Build> {
         let config = self
             .0
             .figment()
@@ -25,14 +79,7 @@ impl shuttle_runtime::Service for RocketService {
             .merge((rocket::Config::LOG_LEVEL, rocket::config::LogLevel::Off))
             .merge((rocket::Config::SHUTDOWN, shutdown));
 
-        let _rocket = self
-            .0
-            .configure(config)
-            .launch()
-            .await
-            .map_err(shuttle_runtime::CustomError::new)?;
-
-        Ok(())
+        self.0.configure(config)
     }
 }
 
@@ -1,6 +1,6 @@
 #![doc = include_str!("../README.md")]
 use salvo::Listener;
-use shuttle_runtime::Error;
+use shuttle_runtime::{Error, HealthChecks, Shutdown};
 use std::net::SocketAddr;
 
 pub use salvo;
@@ -19,6 +19,25 @@ impl shuttle_runtime::Service for SalvoService {
 
         Ok(())
     }
+
+    /// Stops accepting connections on shutdown and waits for in-flight requests to finish
+    /// for up to [`Shutdown::timeout`].
+    async fn bind_with_shutdown(self, addr: SocketAddr, shutdown: Shutdown) -> Result<(), Error> {
+        let listener = salvo::conn::TcpListener::new(addr).bind().await;
+        HealthChecks::global().set_ready();
+        let server = salvo::Server::new(listener);
+
+        let handle = server.handle();
+        shuttle_runtime::tokio::spawn(async move {
+            let timeout = shutdown.timeout();
+            shutdown.await;
+            handle.stop_graceful(timeout);
+        });
+
+        server.serve(self.0).await;
+
+        Ok(())
+    }
 }
 
 impl From<salvo::Router> for SalvoService {
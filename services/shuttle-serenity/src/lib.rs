@@ -1,5 +1,5 @@
 #![doc = include_str!("../README.md")]
-use shuttle_runtime::{CustomError, Error};
+use shuttle_runtime::{CustomError, Error, HealthChecks};
 use std::net::SocketAddr;
 
 #[cfg(feature = "serenity")]
@@ -20,6 +20,8 @@ impl shuttle_runtime::Service for SerenityService {
     /// Takes the client that is returned by the user in their [shuttle_runtime::main] function
     /// and starts it.
     async fn bind(mut self, _addr: SocketAddr) -> Result<(), Error> {
+        // a gateway client does not listen on a port
+        HealthChecks::global().set_ready();
         self.0.start_autosharded().await.map_err(CustomError::new)?;
 
         Ok(())
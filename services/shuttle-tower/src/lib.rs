@@ -1,5 +1,5 @@
 #![doc = include_str!("../README.md")]
-use shuttle_runtime::{CustomError, Error};
+use shuttle_runtime::{CustomError, Error, HealthChecks, Shutdown};
 use std::net::SocketAddr;
 
 pub use tower;
@@ -29,6 +29,20 @@ where
 
         Ok(())
     }
+
+    /// Stops accepting connections on shutdown and waits for in-flight requests to finish.
+    async fn bind_with_shutdown(self, addr: SocketAddr, shutdown: Shutdown) -> Result<(), Error> {
+        let shared = tower::make::Shared::new(self.0);
+        let server = hyper::Server::try_bind(&addr).map_err(CustomError::new)?;
+        HealthChecks::global().set_ready();
+        server
+            .serve(shared)
+            .with_graceful_shutdown(shutdown)
+            .await
+            .map_err(CustomError::new)?;
+
+        Ok(())
+    }
 }
 
 impl<T> From<T> for TowerService<T>
@@ -1,5 +1,5 @@
 #![doc = include_str!("../README.md")]
-use shuttle_runtime::Error;
+use shuttle_runtime::{CustomError, Error, HealthChecks, Shutdown};
 use std::net::SocketAddr;
 use std::ops::Deref;
 
@@ -20,6 +20,16 @@ where
         warp::serve((*self).clone()).run(addr).await;
         Ok(())
     }
+
+    /// Stops accepting connections on shutdown and waits for in-flight requests to finish.
+    async fn bind_with_shutdown(self, addr: SocketAddr, shutdown: Shutdown) -> Result<(), Error> {
+        let (_addr, server) = warp::serve((*self).clone())
+            .try_bind_with_graceful_shutdown(addr, shutdown)
+            .map_err(CustomError::new)?;
+        HealthChecks::global().set_ready();
+        server.await;
+        Ok(())
+    }
 }
 
 impl<T> From<T> for WarpService<T>